@@ -0,0 +1,19 @@
+//! Define `cfg(validit_stripped)`: feature `strip` is enabled and validation is compiled out, i.e.,
+//! neither `debug_assertions` nor feature `release-checks` is enabled.
+//!
+//! Then `Valid<T>` keeps no per-instance state of validation.
+
+use std::env;
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(validit_stripped)");
+
+    let enabled = |name: &str| env::var_os(name).is_some();
+
+    let checks_compiled =
+        enabled("CARGO_CFG_DEBUG_ASSERTIONS") || enabled("CARGO_FEATURE_RELEASE_CHECKS");
+
+    if enabled("CARGO_FEATURE_STRIP") && !checks_compiled {
+        println!("cargo::rustc-cfg=validit_stripped");
+    }
+}
@@ -0,0 +1,151 @@
+//! Parse container level `#[validate(check = "...", name = "...")]` attributes.
+
+use proc_macro2::Punct;
+use proc_macro2::Spacing;
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use quote::quote;
+use quote::quote_spanned;
+use syn::Attribute;
+use syn::BinOp;
+use syn::Expr;
+use syn::LitStr;
+use syn::UnOp;
+use syn::spanned::Spanned;
+
+use crate::constraint::Op;
+
+/// A cross-field invariant such as `self.a <= self.b`.
+pub(crate) struct Check {
+    span: Span,
+    op: Op,
+    left: Expr,
+    right: Expr,
+
+    /// Optional name of the invariant, prepended to the error message.
+    name: Option<LitStr>,
+}
+
+impl Check {
+    /// Build the statements that evaluate this invariant.
+    pub(crate) fn expand(&self) -> TokenStream {
+        let check = self.op.to_macro(self.span, operand(&self.left), operand(&self.right));
+
+        match &self.name {
+            Some(name) => with_context(name.span(), &name.value(), check),
+            None => check,
+        }
+    }
+}
+
+/// Build the tokens of an operand.
+///
+/// A leading unary operator is joined with its operand so that the error message shows `*a`
+/// instead of `* a`.
+fn operand(expr: &Expr) -> TokenStream {
+    let Expr::Unary(unary) = expr else {
+        return expr.to_token_stream();
+    };
+
+    let ch = match unary.op {
+        UnOp::Deref(_) => '*',
+        UnOp::Not(_) => '!',
+        UnOp::Neg(_) => '-',
+        _ => return expr.to_token_stream(),
+    };
+
+    let mut op = Punct::new(ch, Spacing::Joint);
+    op.set_span(unary.op.span());
+
+    let inner = operand(&unary.expr);
+    quote! { #op #inner }
+}
+
+/// Run `checks` in a closure and prepend `context` to the error message if it fails.
+pub(crate) fn with_context(span: Span, context: &str, checks: TokenStream) -> TokenStream {
+    quote_spanned! {span=>
+        #[allow(clippy::redundant_closure_call)]
+        (|| -> ::core::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error>> {
+            #checks
+            ::core::result::Result::Ok(())
+        })()
+        .map_err(|e| ::validit::macros::with_invariant(#context, e))?;
+    }
+}
+
+/// Collect `check` invariants from all `#[validate(...)]` attributes of a struct.
+///
+/// Every attribute declares at most one invariant, with an optional `name`.
+pub(crate) fn parse_checks(attrs: &[Attribute]) -> syn::Result<Vec<Check>> {
+    let mut checks = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident("validate") {
+            continue;
+        }
+
+        let mut expr: Option<LitStr> = None;
+        let mut name: Option<LitStr> = None;
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("check") {
+                if expr.is_some() {
+                    return Err(meta.error("duplicated `check`, use one attribute for each check"));
+                }
+                expr = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown validate attribute, expect one of: check, name"))
+            }
+        })?;
+
+        let Some(expr) = expr else {
+            return Err(syn::Error::new_spanned(attr, "missing `check = \"...\"`"));
+        };
+
+        checks.push(parse_check(expr, name)?);
+    }
+
+    Ok(checks)
+}
+
+/// Parse the expression in `check = "..."`, which must be a comparison.
+///
+/// The parsed tokens carry the span of the string literal, thus errors are reported there.
+fn parse_check(lit: LitStr, name: Option<LitStr>) -> syn::Result<Check> {
+    let span = lit.span();
+    let expr: Expr = lit.parse()?;
+
+    let Expr::Binary(bin) = expr else {
+        return Err(syn::Error::new(
+            span,
+            "expect a comparison such as `self.a <= self.b`",
+        ));
+    };
+
+    let op = match bin.op {
+        BinOp::Lt(_) => Op::Less,
+        BinOp::Le(_) => Op::LessEqual,
+        BinOp::Gt(_) => Op::Greater,
+        BinOp::Ge(_) => Op::GreaterEqual,
+        BinOp::Eq(_) => Op::Equal,
+        _ => {
+            return Err(syn::Error::new(
+                span,
+                "expect one of the comparison operators: <, <=, >, >=, ==",
+            ));
+        }
+    };
+
+    Ok(Check {
+        span,
+        op,
+        left: *bin.left,
+        right: *bin.right,
+        name,
+    })
+}
@@ -0,0 +1,132 @@
+//! Parse `#[validate(...)]` field attributes into constraints.
+
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::Attribute;
+use syn::Expr;
+use syn::RangeLimits;
+use syn::spanned::Spanned;
+
+/// A single constraint declared on a field.
+pub(crate) enum Constraint {
+    /// Compare the field with a value using one of the comparison macros, such as `less!`.
+    Compare { span: Span, op: Op, value: Expr },
+
+    /// Call `Validate::validate()` on the field.
+    Nested { span: Span },
+}
+
+/// Comparison operators, each maps to a macro defined in `validit::macros`.
+#[derive(Clone, Copy)]
+pub(crate) enum Op {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+}
+
+impl Op {
+    fn from_key(key: &str) -> Option<Self> {
+        let op = match key {
+            "less" => Op::Less,
+            "less_equal" => Op::LessEqual,
+            "greater" => Op::Greater,
+            "greater_equal" => Op::GreaterEqual,
+            "equal" => Op::Equal,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Build the macro call that asserts `a <op> b`.
+    pub(crate) fn to_macro(self, span: Span, a: TokenStream, b: TokenStream) -> TokenStream {
+        match self {
+            Op::Less => quote_spanned! {span=> ::validit::less!(#a, #b); },
+            Op::LessEqual => quote_spanned! {span=> ::validit::less_equal!(#a, #b); },
+            Op::Greater => quote_spanned! {span=> ::validit::greater!(#a, #b); },
+            Op::GreaterEqual => quote_spanned! {span=> ::validit::greater_equal!(#a, #b); },
+            Op::Equal => quote_spanned! {span=> ::validit::equal!(#a, #b); },
+        }
+    }
+}
+
+impl Constraint {
+    /// Build the statements that check the field accessed by `field`.
+    pub(crate) fn expand(&self, field: &TokenStream) -> TokenStream {
+        match self {
+            Constraint::Compare { span, op, value } => {
+                op.to_macro(*span, field.clone(), quote_spanned! {*span=> #value})
+            }
+            Constraint::Nested { span } => {
+                quote_spanned! {*span=> ::validit::Validate::validate(&#field)?; }
+            }
+        }
+    }
+}
+
+/// Collect constraints from all `#[validate(...)]` attributes of a field.
+pub(crate) fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<Vec<Constraint>> {
+    let mut constraints = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident("validate") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            let span = meta.path.span();
+            let key = meta.path.get_ident().map(|i| i.to_string()).unwrap_or_default();
+
+            if key == "nested" {
+                constraints.push(Constraint::Nested { span });
+                return Ok(());
+            }
+
+            if key == "range" {
+                let range: Expr = meta.value()?.parse()?;
+                let Expr::Range(range) = range else {
+                    return Err(syn::Error::new(
+                        range.span(),
+                        "expect a range such as `1..5` or `1..=5`",
+                    ));
+                };
+
+                if let Some(start) = range.start {
+                    constraints.push(Constraint::Compare {
+                        span,
+                        op: Op::GreaterEqual,
+                        value: *start,
+                    });
+                }
+
+                if let Some(end) = range.end {
+                    let op = match range.limits {
+                        RangeLimits::HalfOpen(_) => Op::Less,
+                        RangeLimits::Closed(_) => Op::LessEqual,
+                    };
+                    constraints.push(Constraint::Compare {
+                        span,
+                        op,
+                        value: *end,
+                    });
+                }
+                return Ok(());
+            }
+
+            if let Some(op) = Op::from_key(&key) {
+                let value: Expr = meta.value()?.parse()?;
+                constraints.push(Constraint::Compare { span, op, value });
+                return Ok(());
+            }
+
+            Err(meta.error(
+                "unknown validate attribute, expect one of: \
+                 less, less_equal, greater, greater_equal, equal, range, nested",
+            ))
+        })?;
+    }
+
+    Ok(constraints)
+}
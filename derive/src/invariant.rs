@@ -0,0 +1,252 @@
+//! Attribute macro `#[invariant]` that validates around `&mut self` methods of an impl block.
+
+use proc_macro2::Delimiter;
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use quote::ToTokens;
+use quote::quote;
+use quote::quote_spanned;
+use syn::Attribute;
+use syn::FnArg;
+use syn::ImplItem;
+use syn::ImplItemFn;
+use syn::ItemImpl;
+use syn::ReturnType;
+use syn::Type;
+use syn::Visibility;
+use syn::parse_quote;
+use syn::spanned::Spanned;
+
+/// How to report a violated invariant.
+#[derive(Clone, Copy)]
+enum OnError {
+    /// Panic with the error message.
+    Panic,
+
+    /// Return the error if the method returns a `Result`, otherwise panic.
+    ReturnErr,
+}
+
+pub(crate) fn expand(args: TokenStream, mut item: ItemImpl) -> syn::Result<TokenStream> {
+    let on_error = parse_args(args)?;
+
+    // Methods of a trait impl have no visibility and are all part of the public API.
+    let is_trait_impl = item.trait_.is_some();
+    let mut errors = Vec::new();
+
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(method) = impl_item else {
+            continue;
+        };
+
+        let skip = take_skip(&mut method.attrs)?;
+        let exposed = is_trait_impl || !matches!(method.vis, Visibility::Inherited);
+
+        if skip || !exposed || !takes_mut_self(method) {
+            continue;
+        }
+
+        // Leave an unsupported method as is and report it along with the impl block, so that
+        // its callers do not fail to resolve the method.
+        if let Some(asyncness) = &method.sig.asyncness {
+            errors.push(syn::Error::new(
+                asyncness.span(),
+                "#[invariant] does not support async method, mark it with #[invariant(skip)]",
+            ));
+            continue;
+        }
+
+        if let Some(ty) = returns_borrow(&method.sig.output) {
+            errors.push(syn::Error::new_spanned(
+                ty,
+                "#[invariant] does not support method returning a borrow, \
+                 mark it with #[invariant(skip)]",
+            ));
+            continue;
+        }
+
+        wrap_method(method, on_error);
+    }
+
+    let errors = errors.iter().map(syn::Error::to_compile_error);
+    Ok(quote! { #item #(#errors)* })
+}
+
+fn parse_args(args: TokenStream) -> syn::Result<OnError> {
+    let mut on_error = OnError::Panic;
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("return_err") {
+            on_error = OnError::ReturnErr;
+            Ok(())
+        } else {
+            Err(meta.error("unknown invariant attribute, expect: return_err"))
+        }
+    });
+    syn::parse::Parser::parse2(parser, args)?;
+
+    Ok(on_error)
+}
+
+/// Remove `#[invariant(skip)]` from a method and return whether it is found.
+fn take_skip(attrs: &mut Vec<Attribute>) -> syn::Result<bool> {
+    let mut skip = false;
+    let mut res = Ok(());
+
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("invariant") {
+            return true;
+        }
+
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown invariant attribute on method, expect: skip"))
+            }
+        });
+        if let Err(e) = parsed {
+            res = Err(e);
+        }
+        false
+    });
+
+    res.map(|_| skip)
+}
+
+/// Whether the receiver is `&mut self` or `self: &mut Self`.
+fn takes_mut_self(method: &ImplItemFn) -> bool {
+    let Some(FnArg::Receiver(receiver)) = method.sig.inputs.first() else {
+        return false;
+    };
+
+    match receiver.ty.as_ref() {
+        Type::Reference(reference) => {
+            reference.mutability.is_some()
+                && matches!(reference.elem.as_ref(), Type::Path(p) if p.path.is_ident("Self"))
+        }
+        _ => false,
+    }
+}
+
+/// Whether the method returns a `Result`, judged by the name of the return type.
+fn returns_result(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, ty) = output else {
+        return false;
+    };
+    let Type::Path(path) = ty.as_ref() else {
+        return false;
+    };
+    path.path.segments.last().is_some_and(|seg| seg.ident == "Result")
+}
+
+/// Return the return type if it may borrow from `self`: it has a reference or a lifetime other
+/// than `'static`, such as `&mut u64` or `Iter<'_, u64>`.
+///
+/// The body of such a method can not be moved into a closure, see [`wrap_method()`].
+fn returns_borrow(output: &ReturnType) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    has_borrow(ty.to_token_stream()).then_some(ty.as_ref())
+}
+
+/// Whether a type has a reference or a non-`'static` lifetime, ignoring the arguments of a
+/// function type such as `Box<dyn Fn(&u64)>`.
+fn has_borrow(tokens: TokenStream) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        let borrows = match token {
+            // `&'a T` is judged by its lifetime.
+            TokenTree::Punct(p) if p.as_char() == '&' => {
+                !matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '\'')
+            }
+            TokenTree::Punct(p) if p.as_char() == '\'' => {
+                !matches!(tokens.peek(), Some(TokenTree::Ident(i)) if i == "static")
+            }
+            TokenTree::Ident(i) if ["Fn", "FnMut", "FnOnce", "fn"].iter().any(|f| i == f) => {
+                if let Some(TokenTree::Group(g)) = tokens.peek()
+                    && g.delimiter() == Delimiter::Parenthesis
+                {
+                    tokens.next();
+                }
+                false
+            }
+            TokenTree::Group(g) => has_borrow(g.stream()),
+            _ => false,
+        };
+
+        if borrows {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Validate `self` before and after running the original method body.
+///
+/// The body runs in a closure so that an early `return` or `?` still reaches the check after it.
+fn wrap_method(method: &mut ImplItemFn, on_error: OnError) {
+    let span = method.sig.ident.span();
+    let name = method.sig.ident.to_string();
+    let body = &method.block;
+
+    let closure = match &method.sig.output {
+        ReturnType::Type(_, ty) if !matches!(ty.as_ref(), Type::ImplTrait(_)) => {
+            quote_spanned! {span=> (|| -> #ty #body)() }
+        }
+        _ => quote_spanned! {span=> (|| #body)() },
+    };
+
+    let return_err = matches!(on_error, OnError::ReturnErr) && returns_result(&method.sig.output);
+
+    let before = check(span, &name, "before", return_err);
+    let after = check(span, &name, "after", return_err);
+
+    method.attrs.push(parse_quote! { #[track_caller] });
+    method.block = parse_quote! {{
+        let __validit_caller = ::core::panic::Location::caller();
+        #before
+        #[allow(clippy::redundant_closure_call)]
+        let __validit_ret = #closure;
+        #after
+        __validit_ret
+    }};
+}
+
+/// Build the statements that validate `self` and report a violation.
+fn check(span: Span, method: &str, when: &str, return_err: bool) -> TokenStream {
+    let report = if return_err {
+        quote_spanned! {span=>
+            #[allow(clippy::useless_conversion)]
+            let __validit_err = ::core::convert::From::from(__validit_err);
+            return ::core::result::Result::Err(__validit_err);
+        }
+    } else {
+        quote_spanned! {span=>
+            ::core::panic!("{}", __validit_err);
+        }
+    };
+
+    quote_spanned! {span=>
+        if ::validit::CHECKS_COMPILED && ::validit::is_enabled::<Self>() {
+            if let ::core::result::Result::Err(e) = ::validit::Validate::validate(&*self) {
+                let __validit_err = ::validit::macros::with_invariant(
+                    &::std::format!(
+                        "invariant violated {} calling `{}::{}()` at {}",
+                        #when,
+                        ::std::any::type_name::<Self>(),
+                        #method,
+                        __validit_caller,
+                    ),
+                    e,
+                );
+                #report
+            }
+        }
+    }
+}
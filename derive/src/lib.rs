@@ -0,0 +1,250 @@
+//! Derive macro for [`validit::Validate`](https://docs.rs/validit/latest/validit/trait.Validate.html).
+//!
+//! This crate is re-exported by `validit` when the `derive` feature is enabled, use it via
+//! `validit::Validate`.
+
+mod check;
+mod constraint;
+mod invariant;
+
+use proc_macro::TokenStream;
+use proc_macro2::Punct;
+use proc_macro2::Spacing;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::format_ident;
+use quote::quote;
+use syn::Data;
+use syn::DataEnum;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::Ident;
+use syn::Index;
+use syn::ItemImpl;
+use syn::Member;
+use syn::parse_macro_input;
+
+use crate::check::parse_checks;
+use crate::check::with_context;
+use crate::constraint::parse_field_attrs;
+
+/// Derive `validit::Validate` from `#[validate(...)]` attributes on fields, structs and enums.
+///
+/// Supported field attributes:
+/// - `less = v`, `less_equal = v`, `greater = v`, `greater_equal = v`, `equal = v`: compare the
+///   field with `v` using the macro with the same name, such as `validit::less!`.
+/// - `range = a..b` or `range = a..=b`: the field must be in the range. Either end can be omitted.
+/// - `nested`: call `Validate::validate()` on the field.
+///
+/// Several constraints can be put in one attribute, e.g., `#[validate(greater = 0, less = 10)]`.
+/// The error message is the same as the one built by the macros in `validit::macros`, e.g.,
+/// `expect: self.a(20) < 10(10) at src/foo.rs:3`.
+///
+/// Invariants that relate several fields are declared on the struct with
+/// `#[validate(check = "self.a <= self.b")]`. The expression must be a comparison with one of `<`,
+/// `<=`, `>`, `>=`, `==`, and is checked with the corresponding macro so that both sides' values
+/// are shown. An optional `name = "..."` is prepended to the error message, e.g.,
+/// `a_le_b: expect: self.a(3) <= self.b(2) at src/foo.rs:1`.
+///
+/// For an enum, `validate()` checks the active variant. Field attributes and `check` can be put on
+/// variants and their fields. In a variant, fields are bound by reference, named fields with their
+/// own names and tuple fields with `_0`, `_1`, etc., e.g.,
+/// `#[validate(check = "*committed <= *last_log_index")]`. Errors from a variant are prefixed with
+/// the variant name, e.g., `Role::Leader: expect: *committed(6) <= *last_log_index(5) at ...`.
+///
+/// It also implements `TryFrom<Foo> for Valid<Foo>` with `Valid::try_new()`, which can not be
+/// implemented for all `T: Validate` by `validit` because it conflicts with the blanket
+/// `TryFrom<U> for T where U: Into<T>`.
+///
+/// ```ignore
+/// #[derive(validit::Validate)]
+/// #[validate(check = "self.a <= self.b", name = "a_le_b")]
+/// struct Foo {
+///     #[validate(less = 10)]
+///     a: u64,
+///     #[validate(range = 1..=5)]
+///     b: u64,
+///     #[validate(nested)]
+///     c: Bar,
+/// }
+/// ```
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Validate `self` before and after every public `&mut self` method in an impl block.
+///
+/// A method is checked if it takes `&mut self` or `self: &mut Self`, and it is `pub`, including
+/// `pub(crate)` and alike, or it is in a trait impl. Private methods are NOT checked: they are
+/// helpers that may run while an invariant is temporarily broken. Methods with other receivers,
+/// such as `self: Pin<&mut Self>`, are not checked either.
+///
+/// A violation panics with a message naming the method and its call site, e.g.,
+/// ``invariant violated after calling `foo::Foo::push()` at src/main.rs:10:5: expect: ...``.
+/// With `#[invariant(return_err)]`, methods returning a `Result` return the error instead, it is
+/// converted with `From<Box<dyn Error>>` into the error type of the method. A failed check of the
+/// validation macros stays a `validit::ValidationError`, with the method and the call site as its
+/// `invariant`.
+///
+/// Methods marked with `#[invariant(skip)]` are left untouched. Async methods and methods whose
+/// return type has a reference or a non-`'static` lifetime, such as `-> &mut u64` or
+/// `-> Iter<'_, u64>`, are not supported: they are rejected with a compile error asking to mark
+/// them with `#[invariant(skip)]`. A borrow hidden behind an elided lifetime, such as
+/// `-> Iter<u64>`, is not detected, write it as `Iter<'_, u64>`.
+///
+/// Like [`Valid`](https://docs.rs/validit/latest/validit/struct.Valid.html), the checks run
+/// only when validation is compiled in, see `validit::CHECKS_COMPILED`, and follow the process-wide
+/// setting of [`validit::global`](https://docs.rs/validit/latest/validit/global/index.html).
+///
+/// ```ignore
+/// #[validit::invariant]
+/// impl Foo {
+///     pub fn push(&mut self, x: u64) {
+///         self.a += x;
+///     }
+///
+///     #[invariant(skip)]
+///     pub fn reset(&mut self) {
+///         self.a = 0;
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn invariant(args: TokenStream, input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemImpl);
+    match invariant::expand(args.into(), item) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut checks = match &input.data {
+        Data::Struct(data) => expand_fields(&data.fields, |member| quote! { self.#member })?,
+        Data::Enum(data) if data.variants.is_empty() => {
+            // An empty enum can not be instantiated, there is nothing to check.
+            quote! { match *self {} }
+        }
+        Data::Enum(data) => expand_enum(&input.ident, data)?,
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(Validate)] only supports struct and enum",
+            ));
+        }
+    };
+
+    for check in parse_checks(&input.attrs)? {
+        checks.extend(check.expand());
+    }
+
+    // The trailing `Ok(())` is unreachable for an empty enum.
+    let allow = match &input.data {
+        Data::Enum(data) if data.variants.is_empty() => quote! { #[allow(unreachable_code)] },
+        _ => quote! {},
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::validit::Validate for #name #ty_generics #where_clause {
+            #allow
+            fn validate(&self) -> ::core::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error>> {
+                #checks
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<#name #ty_generics> for ::validit::Valid<#name #ty_generics> #where_clause {
+            type Error = ::std::boxed::Box<dyn ::std::error::Error>;
+
+            fn try_from(inner: #name #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                ::validit::Valid::try_new(inner)
+            }
+        }
+    })
+}
+
+/// Build a `match` that checks the fields and invariants of the active variant.
+///
+/// Fields are bound by reference, named fields with their own name and tuple fields with `_0`,
+/// `_1`, ... Errors are prefixed with the variant name, such as `Role::Leader: `.
+fn expand_enum(name: &Ident, data: &DataEnum) -> syn::Result<TokenStream2> {
+    let mut arms = TokenStream2::new();
+
+    for variant in &data.variants {
+        let ident = &variant.ident;
+
+        let bindings = variant.fields.iter().enumerate().map(|(i, field)| match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("_{}", i),
+        });
+        let bindings = bindings.collect::<Vec<_>>();
+
+        let pattern = match &variant.fields {
+            Fields::Named(_) => quote! { Self::#ident { #(#bindings),* } },
+            Fields::Unnamed(_) => quote! { Self::#ident ( #(#bindings),* ) },
+            Fields::Unit => quote! { Self::#ident },
+        };
+
+        let mut checks = expand_fields(&variant.fields, |member| {
+            let binding = match member {
+                Member::Named(ident) => ident,
+                Member::Unnamed(index) => format_ident!("_{}", index.index),
+            };
+            // A joint `*` so that the error message shows `*a` instead of `* a`.
+            let deref = Punct::new('*', Spacing::Joint);
+            quote! { #deref #binding }
+        })?;
+
+        for check in parse_checks(&variant.attrs)? {
+            checks.extend(check.expand());
+        }
+
+        let body = if checks.is_empty() {
+            checks
+        } else {
+            with_context(ident.span(), &format!("{}::{}", name, ident), checks)
+        };
+
+        arms.extend(quote! {
+            #[allow(unused_variables)]
+            #pattern => { #body }
+        });
+    }
+
+    Ok(quote! {
+        match self {
+            #arms
+        }
+    })
+}
+
+/// Build checks for every field with `#[validate(...)]` attributes.
+///
+/// `access` builds the expression to access a field, such as `self.a`.
+fn expand_fields(
+    fields: &Fields,
+    access: impl Fn(Member) -> TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let mut checks = TokenStream2::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        };
+        let access = access(member);
+
+        for constraint in parse_field_attrs(&field.attrs)? {
+            checks.extend(constraint.expand(&access));
+        }
+    }
+
+    Ok(checks)
+}
@@ -0,0 +1,4 @@
+mod t10_derive_enum;
+mod t10_derive_struct;
+mod t10_derive_struct_check;
+mod t20_invariant;
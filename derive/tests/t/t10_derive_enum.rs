@@ -0,0 +1,78 @@
+use validit::Validate;
+
+#[derive(Validate)]
+#[allow(dead_code)]
+enum Role {
+    #[validate(check = "*committed <= *last_log_index")]
+    Leader {
+        #[validate(greater = 0)]
+        term: u64,
+        committed: u64,
+        last_log_index: u64,
+    },
+
+    Follower(#[validate(less = 10)] u64, #[validate(nested)] Voter),
+
+    #[validate(check = "_0 == _1")]
+    Learner(u64, u64),
+
+    Idle,
+}
+
+#[derive(Validate)]
+struct Voter {
+    #[validate(equal = 1)]
+    weight: u64,
+}
+
+#[derive(Validate)]
+enum Never {}
+
+fn err_of(v: &impl Validate) -> String {
+    v.validate().unwrap_err().to_string()
+}
+
+#[test]
+fn test_derive_enum_ok() {
+    let r = Role::Leader {
+        term: 1,
+        committed: 3,
+        last_log_index: 5,
+    };
+    assert!(r.validate().is_ok());
+
+    assert!(Role::Follower(3, Voter { weight: 1 }).validate().is_ok());
+    assert!(Role::Learner(2, 2).validate().is_ok());
+    assert!(Role::Idle.validate().is_ok());
+
+    fn assert_validate<T: Validate>() {}
+    assert_validate::<Never>();
+}
+
+#[test]
+fn test_derive_enum_error_message() {
+    let r = Role::Leader {
+        term: 0,
+        committed: 3,
+        last_log_index: 5,
+    };
+    assert!(err_of(&r).starts_with("Role::Leader: expect: *term(0) > 0(0) at "));
+
+    let r = Role::Leader {
+        term: 1,
+        committed: 6,
+        last_log_index: 5,
+    };
+    assert!(
+        err_of(&r).starts_with("Role::Leader: expect: *committed(6) <= *last_log_index(5) at ")
+    );
+
+    let r = Role::Follower(10, Voter { weight: 1 });
+    assert!(err_of(&r).starts_with("Role::Follower: expect: *_0(10) < 10(10) at "));
+
+    let r = Role::Follower(3, Voter { weight: 2 });
+    assert!(err_of(&r).starts_with("Role::Follower: expect: self.weight(2) == 1(1) at "));
+
+    let r = Role::Learner(2, 3);
+    assert!(err_of(&r).starts_with("Role::Learner: expect: _0(2) == _1(3) at "));
+}
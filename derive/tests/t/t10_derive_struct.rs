@@ -0,0 +1,118 @@
+use validit::Valid;
+use validit::Validate;
+
+#[derive(Validate)]
+struct Foo {
+    #[validate(less = 10)]
+    a: u64,
+
+    #[validate(range = 1..=5)]
+    b: u64,
+
+    #[validate(greater = 0, less_equal = 3)]
+    c: u64,
+
+    #[validate(nested)]
+    bar: Bar,
+
+    _no_check: u64,
+}
+
+#[derive(Validate)]
+struct Bar {
+    #[validate(equal = 7)]
+    x: u64,
+}
+
+#[derive(Validate)]
+struct Tuple(
+    #[validate(range = 2..4)] u64,
+    #[validate(greater_equal = 1)] i32,
+);
+
+fn foo() -> Foo {
+    Foo {
+        a: 1,
+        b: 1,
+        c: 1,
+        bar: Bar { x: 7 },
+        _no_check: 100,
+    }
+}
+
+fn err_of(v: &impl Validate) -> String {
+    v.validate().unwrap_err().to_string()
+}
+
+#[test]
+fn test_derive_struct_ok() {
+    assert!(foo().validate().is_ok());
+    assert!(Tuple(2, 1).validate().is_ok());
+    assert!(Tuple(3, 100).validate().is_ok());
+}
+
+#[test]
+fn test_derive_struct_error_message() {
+    let f = Foo { a: 10, ..foo() };
+    assert!(err_of(&f).starts_with("expect: self.a(10) < 10(10) at "));
+
+    let f = Foo { b: 0, ..foo() };
+    assert!(err_of(&f).starts_with("expect: self.b(0) >= 1(1) at "));
+
+    let f = Foo { b: 6, ..foo() };
+    assert!(err_of(&f).starts_with("expect: self.b(6) <= 5(5) at "));
+
+    let f = Foo { c: 0, ..foo() };
+    assert!(err_of(&f).starts_with("expect: self.c(0) > 0(0) at "));
+
+    let f = Foo { c: 4, ..foo() };
+    assert!(err_of(&f).starts_with("expect: self.c(4) <= 3(3) at "));
+
+    assert!(err_of(&Tuple(4, 1)).starts_with("expect: self.0(4) < 4(4) at "));
+    assert!(err_of(&Tuple(2, 0)).starts_with("expect: self.1(0) >= 1(1) at "));
+}
+
+#[test]
+fn test_derive_struct_error_location() {
+    let f = Foo { a: 10, ..foo() };
+    let got = err_of(&f);
+    assert!(got.ends_with(&format!("{}:{}", file!(), 6)), "got: {}", got);
+}
+
+#[test]
+fn test_derive_struct_nested() {
+    let f = Foo {
+        bar: Bar { x: 8 },
+        ..foo()
+    };
+    assert!(err_of(&f).starts_with("expect: self.x(8) == 7(7) at "));
+}
+
+#[test]
+fn test_derive_struct_valid() {
+    let _a = Valid::new(foo()).a;
+
+    let res = std::panic::catch_unwind(|| {
+        let _a = Valid::new(Foo { a: 20, ..foo() }).a;
+    });
+    assert!(res.is_err());
+}
+
+#[derive(Validate)]
+struct Generic<T: PartialOrd<u64> + std::fmt::Debug + Copy> {
+    #[validate(less = 10u64)]
+    a: T,
+}
+
+#[test]
+fn test_derive_struct_try_from() {
+    let v: Valid<Foo> = foo().try_into().unwrap();
+    assert_eq!(1, v.a);
+
+    let err = Valid::try_from(Foo { a: 20, ..foo() }).err().unwrap();
+    assert!(err.to_string().starts_with("expect: self.a(20) < 10(10) at "));
+
+    let v: Valid<Generic<u64>> = Generic { a: 3 }.try_into().unwrap();
+    assert_eq!(3, v.a);
+    assert!(Valid::try_from(Generic { a: 30 }).is_err());
+}
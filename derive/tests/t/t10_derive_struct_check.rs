@@ -0,0 +1,81 @@
+use validit::Validate;
+
+#[derive(Validate)]
+#[validate(
+    check = "self.committed <= self.last_log_index",
+    name = "committed_le_last"
+)]
+#[validate(check = "self.term > 0")]
+struct Log {
+    #[validate(less = 100)]
+    committed: u64,
+    last_log_index: u64,
+    term: u64,
+}
+
+#[derive(Validate)]
+#[validate(check = "self.0 == self.1")]
+struct Pair(u64, u64);
+
+fn err_of(v: &impl Validate) -> String {
+    v.validate().unwrap_err().to_string()
+}
+
+#[test]
+fn test_derive_struct_check() {
+    let l = Log {
+        committed: 3,
+        last_log_index: 5,
+        term: 1,
+    };
+    assert!(l.validate().is_ok());
+
+    let l = Log {
+        committed: 6,
+        last_log_index: 5,
+        term: 1,
+    };
+    assert!(
+        err_of(&l).starts_with(
+            "committed_le_last: expect: self.committed(6) <= self.last_log_index(5) at "
+        )
+    );
+
+    let l = Log {
+        committed: 3,
+        last_log_index: 5,
+        term: 0,
+    };
+    assert!(err_of(&l).starts_with("expect: self.term(0) > 0(0) at "));
+
+    assert!(Pair(1, 1).validate().is_ok());
+    assert!(err_of(&Pair(1, 2)).starts_with("expect: self.0(1) == self.1(2) at "));
+}
+
+#[test]
+fn test_derive_struct_check_after_field_constraints() {
+    // Field constraints are checked before struct level invariants.
+    let l = Log {
+        committed: 200,
+        last_log_index: 5,
+        term: 0,
+    };
+    assert!(err_of(&l).starts_with("expect: self.committed(200) < 100(100) at "));
+}
+
+#[test]
+fn test_derive_struct_check_structured_error() {
+    let l = Log {
+        committed: 6,
+        last_log_index: 5,
+        term: 1,
+    };
+
+    let err = l.validate().unwrap_err();
+    let err = err.downcast_ref::<validit::ValidationError>().unwrap();
+
+    assert_eq!(validit::OpKind::LessEqual, err.op);
+    assert_eq!("self.committed", err.left().unwrap().expr);
+    assert_eq!("5", err.right().unwrap().value);
+    assert_eq!(Some("committed_le_last"), err.invariant.as_deref());
+}
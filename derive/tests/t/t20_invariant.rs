@@ -0,0 +1,275 @@
+use std::error::Error;
+
+use validit::OpKind;
+use validit::Validate;
+use validit::ValidationError;
+
+#[derive(Validate, Default, Debug)]
+struct Counter {
+    #[validate(less = 10)]
+    n: u64,
+}
+
+#[validit::invariant]
+impl Counter {
+    pub fn add(&mut self, x: u64) -> u64 {
+        if x == 0 {
+            return self.n;
+        }
+        self.n += x;
+        self.n
+    }
+
+    pub fn try_add(&mut self, x: u64) -> Result<u64, Box<dyn Error>> {
+        self.n += x;
+        Ok(self.n)
+    }
+
+    #[invariant(skip)]
+    pub fn set(&mut self, n: u64) {
+        self.n = n;
+    }
+
+    pub fn get(&self) -> u64 {
+        self.n
+    }
+
+    fn private_set(&mut self, n: u64) {
+        self.n = n;
+    }
+}
+
+#[derive(Validate, Default)]
+struct Returning {
+    #[validate(less = 10)]
+    n: u64,
+}
+
+#[validit::invariant(return_err)]
+impl Returning {
+    pub fn try_add(&mut self, x: u64) -> Result<u64, Box<dyn Error>> {
+        self.n += x;
+        Ok(self.n)
+    }
+
+    pub fn add(&mut self, x: u64) {
+        self.n += x;
+    }
+}
+
+fn panic_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    let err = std::panic::catch_unwind(f).unwrap_err();
+    match err.downcast::<String>() {
+        Ok(s) => *s,
+        Err(e) => e.downcast_ref::<&str>().unwrap().to_string(),
+    }
+}
+
+#[test]
+fn test_invariant_ok() {
+    let mut c = Counter::default();
+    assert_eq!(3, c.add(3));
+    assert_eq!(3, c.add(0));
+    assert_eq!(5, c.try_add(2).unwrap());
+    assert_eq!(5, c.get());
+}
+
+#[test]
+fn test_invariant_panic_after() {
+    let msg = panic_message(|| {
+        let mut c = Counter::default();
+        c.add(10);
+    });
+    let line = line!() - 2;
+
+    assert!(
+        msg.starts_with("invariant violated after calling `"),
+        "got: {}",
+        msg
+    );
+    assert!(msg.contains("Counter::add()` at "), "got: {}", msg);
+    assert!(
+        msg.contains(&format!("{}:{}:", file!(), line)),
+        "got: {}",
+        msg
+    );
+    assert!(
+        msg.contains(": expect: self.n(10) < 10(10) at "),
+        "got: {}",
+        msg
+    );
+}
+
+#[test]
+fn test_invariant_panic_before() {
+    let msg = panic_message(|| {
+        let mut c = Counter::default();
+        c.private_set(20);
+        c.add(1);
+    });
+    assert!(
+        msg.starts_with("invariant violated before calling `"),
+        "got: {}",
+        msg
+    );
+}
+
+#[test]
+fn test_invariant_skip() {
+    let mut c = Counter::default();
+    c.set(20);
+    assert_eq!(20, c.get());
+}
+
+#[test]
+fn test_invariant_return_err() {
+    let mut r = Returning::default();
+    assert_eq!(3, r.try_add(3).unwrap());
+
+    let err = r.try_add(7).unwrap_err().to_string();
+    assert!(
+        err.starts_with("invariant violated after calling `"),
+        "got: {}",
+        err
+    );
+
+    let err = r.try_add(0).unwrap_err().to_string();
+    assert!(
+        err.starts_with("invariant violated before calling `"),
+        "got: {}",
+        err
+    );
+
+    // A failed check of the validation macros is returned as a `ValidationError`.
+    let err = r.try_add(0).unwrap_err();
+    let err = err.downcast_ref::<ValidationError>().unwrap();
+    assert_eq!(OpKind::Less, err.op);
+    assert_eq!("self.n", err.left().unwrap().expr);
+    assert_eq!("10", err.left().unwrap().value);
+    let invariant = err.invariant.as_deref().unwrap();
+    assert!(
+        invariant.starts_with("invariant violated before calling `"),
+        "got: {}",
+        invariant
+    );
+    assert!(
+        invariant.contains("Returning::try_add()` at "),
+        "got: {}",
+        invariant
+    );
+
+    // Methods not returning a `Result` still panic.
+    let res = std::panic::catch_unwind(|| {
+        let mut r = Returning::default();
+        r.add(10);
+    });
+    assert!(res.is_err());
+}
+
+#[derive(Validate, Default)]
+struct Scoped {
+    #[validate(less = 10)]
+    n: u64,
+}
+
+#[validit::invariant]
+impl Scoped {
+    pub(crate) fn add(&mut self, x: u64) {
+        self.n += x;
+    }
+
+    #[allow(clippy::needless_arbitrary_self_type)]
+    pub fn typed_add(self: &mut Self, x: u64) {
+        self.n += x;
+    }
+
+    fn private_add(&mut self, x: u64) {
+        self.n += x;
+    }
+}
+
+trait Add {
+    fn trait_add(&mut self, x: u64);
+}
+
+#[validit::invariant]
+impl Add for Scoped {
+    fn trait_add(&mut self, x: u64) {
+        self.n += x;
+    }
+}
+
+#[test]
+fn test_invariant_method_selection() {
+    let msg = panic_message(|| Scoped::default().add(10));
+    assert!(msg.contains("Scoped::add()` at "), "got: {}", msg);
+
+    let msg = panic_message(|| Scoped::default().typed_add(10));
+    assert!(msg.contains("Scoped::typed_add()` at "), "got: {}", msg);
+
+    let msg = panic_message(|| Scoped::default().trait_add(10));
+    assert!(msg.contains("Scoped::trait_add()` at "), "got: {}", msg);
+
+    // Private methods are not checked.
+    let mut s = Scoped::default();
+    s.private_add(10);
+    assert_eq!(10, s.n);
+}
+
+#[test]
+fn test_invariant_try_get_validation_error() {
+    let v = validit::Valid::new(Counter { n: 10 });
+    let err = v.try_get().unwrap_err();
+    let err = err.source().unwrap().downcast_ref::<ValidationError>().unwrap();
+    assert_eq!(OpKind::Less, err.op);
+    assert_eq!("10", err.left().unwrap().value);
+    assert_eq!("10", err.right().unwrap().value);
+}
+
+#[derive(Validate, Default)]
+struct Borrowing {
+    #[validate(less = 10)]
+    n: u64,
+}
+
+#[validit::invariant]
+impl Borrowing {
+    /// A `'static` borrow does not borrow `self` and is still checked.
+    pub fn bump(&mut self) -> &'static str {
+        self.n += 5;
+        "bumped"
+    }
+
+    /// Borrowing `&u64` in the argument of a returned closure is still checked.
+    pub fn bump_with(&mut self) -> Box<dyn Fn(&u64) -> bool> {
+        self.n += 5;
+        Box::new(|x| *x < 10)
+    }
+
+    /// A method returning a borrow of `self` must be skipped.
+    #[invariant(skip)]
+    pub fn get_mut(&mut self) -> &mut u64 {
+        &mut self.n
+    }
+}
+
+#[test]
+fn test_invariant_borrowing_return() {
+    let mut b = Borrowing::default();
+    assert_eq!("bumped", b.bump());
+    let msg = panic_message(move || {
+        b.bump();
+    });
+    assert!(msg.contains("Borrowing::bump()` at "), "got: {}", msg);
+
+    let mut b = Borrowing::default();
+    assert!(b.bump_with()(&3));
+    let msg = panic_message(move || {
+        let _f = b.bump_with();
+    });
+    assert!(msg.contains("Borrowing::bump_with()` at "), "got: {}", msg);
+
+    let mut b = Borrowing::default();
+    *b.get_mut() = 20;
+    assert_eq!(20, b.n);
+}
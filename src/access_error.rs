@@ -0,0 +1,173 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::panic::Location;
+
+/// The error of accessing an invalid [`Valid<T>`](crate::Valid), returned by
+/// [`Valid::try_get()`](crate::Valid::try_get) and passed to the policy.
+///
+/// It keeps the error returned by [`Validate::validate()`](crate::Validate::validate) as its
+/// [`source()`](Error::source), and renders it with where the invalid state is detected:
+///
+/// ```text
+/// expect: self.a(20) <= 10(10) at src/foo.rs:10
+/// detected in `my_crate::Foo` at src/main.rs:12:13, last mutated at src/main.rs:11:5
+/// ```
+///
+/// ```
+/// # use std::error::Error;
+/// # use validit::Valid;
+/// # use validit::Validate;
+/// # use validit::ValidationError;
+/// #[derive(Debug)]
+/// struct Foo { a: u64 }
+/// impl Validate for Foo {
+///     fn validate(&self) -> Result<(), Box<dyn Error>> {
+///         validit::less_equal!(self.a, 10);
+///         Ok(())
+///     }
+/// }
+///
+/// let f = Valid::new(Foo { a: 20 });
+/// let err = f.try_get().unwrap_err();
+///
+/// let source = err.source().unwrap().downcast_ref::<ValidationError>().unwrap();
+/// assert_eq!("20", source.left().unwrap().value);
+/// ```
+#[derive(Debug)]
+pub struct AccessError {
+    source: Box<dyn Error>,
+
+    kind: AccessKind,
+
+    /// More information about the invalid state provided by the policy, such as a diff.
+    context: Option<String>,
+
+    type_name: &'static str,
+
+    location: &'static Location<'static>,
+
+    #[cfg(feature = "track-mutation")]
+    last_mutation: Option<&'static Location<'static>>,
+}
+
+/// How the invalid state of an [`AccessError`] is detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    /// By accessing the value, such as with `Deref` or [`Valid::try_get()`](crate::Valid::try_get).
+    Access,
+
+    /// By acquiring a lock in [`sync`](crate::sync) that protects the value. The location is
+    /// where the lock is acquired.
+    Lock,
+
+    /// By dropping a write guard, such as the one returned by
+    /// [`Valid::write()`](crate::Valid::write) or a lock in [`sync`](crate::sync). The location is
+    /// where the guard is created.
+    AfterWrite,
+}
+
+impl AccessError {
+    pub(crate) fn new(
+        source: Box<dyn Error>,
+        type_name: &'static str,
+        location: &'static Location<'static>,
+    ) -> Self {
+        Self {
+            source,
+            kind: AccessKind::Access,
+            context: None,
+            type_name,
+            location,
+            #[cfg(feature = "track-mutation")]
+            last_mutation: None,
+        }
+    }
+
+    pub(crate) fn with_kind(mut self, kind: AccessKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub(crate) fn with_context(mut self, context: Option<String>) -> Self {
+        self.context = context;
+        self
+    }
+
+    #[cfg(feature = "track-mutation")]
+    pub(crate) fn with_last_mutation(
+        mut self,
+        last_mutation: Option<&'static Location<'static>>,
+    ) -> Self {
+        self.last_mutation = last_mutation;
+        self
+    }
+
+    /// The name of the type of the invalid value, such as `my_crate::Foo`.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// How the invalid state is detected.
+    pub fn kind(&self) -> AccessKind {
+        self.kind
+    }
+
+    /// Where the invalid state is detected: the caller that accesses it or locks it, or where
+    /// the write guard is created.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// More information about the invalid state provided by the policy, such as the diff from
+    /// the last valid state by [`Snapshot`](crate::policy::Snapshot).
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// Where the value is mutated the last time, recorded with feature `track-mutation`.
+    #[cfg(feature = "track-mutation")]
+    pub fn last_mutation(&self) -> Option<&'static Location<'static>> {
+        self.last_mutation
+    }
+
+    /// Consume self and return the error from [`Validate::validate()`](crate::Validate::validate).
+    pub fn into_source(self) -> Box<dyn Error> {
+        self.source
+    }
+}
+
+impl Display for AccessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)?;
+
+        if let Some(context) = &self.context {
+            write!(f, "\n{}", context)?;
+        }
+
+        let when = match self.kind {
+            AccessKind::Access => "at",
+            AccessKind::Lock => "when locked at",
+            AccessKind::AfterWrite => "after write at",
+        };
+        write!(
+            f,
+            "\ndetected in `{}` {} {}",
+            self.type_name, when, self.location
+        )?;
+
+        #[cfg(feature = "track-mutation")]
+        if let Some(mutated) = self.last_mutation {
+            write!(f, ", last mutated at {}", mutated)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for AccessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
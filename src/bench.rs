@@ -4,9 +4,12 @@ use std::error::Error;
 use test::Bencher;
 use test::black_box;
 
+use crate::Sampling;
 use crate::Valid;
 use crate::Validate;
+use crate::less;
 use crate::less_equal;
+use crate::policy::Sampled;
 
 struct Foo {
     a: u64,
@@ -27,3 +30,59 @@ fn valid_deref(b: &mut Bencher) {
         let _x = black_box(f.a);
     })
 }
+
+/// A type whose validation walks through all of its entries.
+struct Log {
+    entries: Vec<u64>,
+}
+
+impl Log {
+    fn new() -> Self {
+        Self {
+            entries: (0..1000).collect(),
+        }
+    }
+}
+
+impl Validate for Log {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        for w in self.entries.windows(2) {
+            less!(w[0], w[1]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "dirty-tracking")]
+#[bench]
+fn valid_deref_expensive_clean(b: &mut Bencher) {
+    let l = Valid::new(Log::new());
+
+    b.iter(|| {
+        let _x = black_box(l.entries.len());
+    })
+}
+
+#[bench]
+fn valid_deref_expensive_always(b: &mut Bencher) {
+    #[cfg_attr(not(feature = "dirty-tracking"), allow(unused_mut))]
+    let mut l = Valid::new(Log::new());
+    #[cfg(feature = "dirty-tracking")]
+    l.enable_dirty_tracking(false);
+
+    b.iter(|| {
+        let _x = black_box(l.entries.len());
+    })
+}
+
+#[bench]
+fn valid_deref_expensive_every_100(b: &mut Bencher) {
+    #[cfg_attr(not(feature = "dirty-tracking"), allow(unused_mut))]
+    let mut l = Valid::with_policy(Log::new(), Sampled::new(Sampling::Every(100)));
+    #[cfg(feature = "dirty-tracking")]
+    l.enable_dirty_tracking(false);
+
+    b.iter(|| {
+        let _x = black_box(l.entries.len());
+    })
+}
@@ -0,0 +1,60 @@
+//! Remember which values are validated and not mutated since then.
+//!
+//! `Valid<T>` is `Copy` if `T` is, thus it can not hold a flag that is updated through `&self`.
+//! Instead, every `Valid` carries a generation, which is replaced with a new one whenever a mutable
+//! reference to the value is handed out. A successful validation records the address and the
+//! generation of the value in a small thread local cache. A value found in the cache with the
+//! same generation has not been mutated since it was validated.
+//!
+//! The cache is lossy: an entry may be overwritten by another value, which just causes one more
+//! validation.
+
+use std::cell::Cell;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+const SLOTS: usize = 64;
+
+/// The number of generations a thread reserves at a time, so that marking a value dirty does not
+/// touch a shared atomic.
+const BLOCK: u64 = 1 << 20;
+
+/// The first generation of the next block to reserve. Generation `0` is never assigned, thus an
+/// empty slot never matches.
+#[cfg_attr(validit_stripped, allow(dead_code))]
+static NEXT_BLOCK: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static CLEAN: [Cell<(usize, u64)>; SLOTS] = const { [const { Cell::new((0, 0)) }; SLOTS] };
+
+    /// The next generation to assign and the end of the block reserved by this thread.
+    static GENERATIONS: Cell<(u64, u64)> = const { Cell::new((0, 0)) };
+}
+
+/// Return a generation that has never been assigned.
+#[cfg_attr(validit_stripped, allow(dead_code))]
+pub(crate) fn next_generation() -> u64 {
+    GENERATIONS.with(|g| {
+        let (mut next, mut end) = g.get();
+        if next == end {
+            next = NEXT_BLOCK.fetch_add(BLOCK, Ordering::Relaxed);
+            end = next + BLOCK;
+        }
+        g.set((next + 1, end));
+        next
+    })
+}
+
+fn slot(addr: usize) -> usize {
+    (addr >> 3) % SLOTS
+}
+
+/// Return whether the value at `addr` is validated at `generation`.
+pub(crate) fn is_clean(addr: usize, generation: u64) -> bool {
+    CLEAN.with(|slots| slots[slot(addr)].get() == (addr, generation))
+}
+
+/// Record that the value at `addr` is validated at `generation`.
+pub(crate) fn set_clean(addr: usize, generation: u64) {
+    CLEAN.with(|slots| slots[slot(addr)].set((addr, generation)))
+}
@@ -0,0 +1,94 @@
+use std::cell::Cell;
+use std::error::Error;
+
+use crate::Valid;
+use crate::Validate;
+use crate::less_equal;
+use crate::testing::Counted;
+
+#[test]
+#[allow(clippy::clone_on_copy)]
+fn test_dirty_tracking_count() {
+    let validated = Counted::<30>::validated;
+
+    let mut v = Valid::new(Counted::<30> { a: 1 });
+    assert!(v.is_dirty_tracking_enabled());
+
+    let _x = v.a;
+    let _x = v.a;
+    let _x = v.a;
+    assert_eq!(1, validated(), "a clean value is validated once");
+
+    v.a = 2;
+    assert_eq!(1, validated(), "DerefMut does not validate a clean value");
+
+    let _x = v.a;
+    let _x = v.a;
+    assert_eq!(2, validated(), "a mutated value is validated again");
+
+    // A copy or a clone is at another address, it is validated again.
+    let c = v;
+    let _x = c.a;
+    let _x = c.a;
+    assert_eq!(3, validated());
+
+    let c = v.clone();
+    let _x = c.a;
+    assert_eq!(
+        4,
+        validated(),
+        "the clone is validated, the clean source is not"
+    );
+
+    // Disable dirty tracking
+    v.enable_dirty_tracking(false);
+    assert!(!v.is_dirty_tracking_enabled());
+
+    let _x = v.a;
+    let _x = v.a;
+    assert_eq!(6, validated());
+}
+
+#[test]
+fn test_dirty_tracking_detects_mutation() {
+    // Panic when the value is mutated to an invalid state after a validation.
+    let res = std::panic::catch_unwind(|| {
+        let mut v = Valid::new(Counted::<31> { a: 1 });
+        let _x = v.a;
+        v.a = 20;
+        let _x = v.a;
+    });
+    assert!(res.is_err());
+
+    // try_get_mut() marks the value dirty
+    let mut v = Valid::new(Counted::<31> { a: 1 });
+    let _x = v.try_get().unwrap();
+    v.try_get_mut().unwrap().a = 20;
+    assert!(v.try_get().is_err());
+}
+
+#[test]
+fn test_dirty_tracking_interior_mutability() {
+    struct Shared {
+        a: Cell<u64>,
+    }
+
+    impl Validate for Shared {
+        fn validate(&self) -> Result<(), Box<dyn Error>> {
+            less_equal!(self.a.get(), 10);
+            Ok(())
+        }
+    }
+
+    // A mutation through a shared reference is not noticed with dirty tracking.
+    let v = Valid::new(Shared { a: Cell::new(1) });
+    let _x = v.try_get().unwrap();
+    v.a.set(20);
+    assert!(v.try_get().is_ok());
+
+    let mut v = Valid::new(Shared { a: Cell::new(1) });
+    v.enable_dirty_tracking(false);
+    let _x = v.try_get().unwrap();
+    v.a.set(20);
+    assert!(v.try_get().is_err());
+}
@@ -0,0 +1,66 @@
+//! Report failed validations with `tracing` or `log`, enabled by feature `tracing` or `log`.
+//!
+//! Every failed call to [`Validate::validate()`](crate::Validate::validate) made by this crate
+//! emits an `ERROR` event with target `validit` and message `validation failed`, with fields:
+//! - `type_name`: the type name of the value, such as `my_crate::Foo`;
+//! - `invariant`: the violated invariant, such as `expect: self.a(20) <= 10(10)`;
+//! - `file` and `line`: where the invariant is defined, or `""` and `0` if unknown;
+//! - `error`: the complete error message.
+//!
+//! The `invariant`, `file` and `line` are taken from the [`ValidationError`] returned by
+//! `validate()`, or found in its [`source()`](Error::source) chain. For any other error, the
+//! `invariant` is the error message and the location is unknown.
+
+use std::any::type_name;
+use std::error::Error;
+
+use crate::ValidationError;
+
+/// Emit an event for a failed validation of a value of type `T`.
+pub(crate) fn failed<T: ?Sized>(err: &(dyn Error + 'static)) {
+    let type_name = type_name::<T>();
+    let error = err.to_string();
+    let (invariant, file, line) = describe(err);
+    let invariant = invariant.as_str();
+
+    #[cfg(feature = "tracing")]
+    tracing::error!(
+        target: "validit",
+        type_name,
+        invariant,
+        file,
+        line,
+        error = error.as_str(),
+        "validation failed"
+    );
+
+    #[cfg(feature = "log")]
+    log::error!(
+        target: "validit",
+        type_name = type_name,
+        invariant = invariant,
+        file = file,
+        line = line,
+        error = error.as_str();
+        "validation failed: {}",
+        error
+    );
+}
+
+/// Return the violated invariant and where it is defined, from the first [`ValidationError`] in
+/// the chain of `err`, or the message of `err` and an unknown location.
+pub(crate) fn describe(err: &(dyn Error + 'static)) -> (String, &'static str, u32) {
+    let found = std::iter::successors(Some(err), |&e| e.source())
+        .find_map(|e| e.downcast_ref::<ValidationError>());
+
+    match found {
+        Some(e) => {
+            let invariant = match &e.invariant {
+                Some(name) => format!("{}: {}", name, e.expectation()),
+                None => e.expectation(),
+            };
+            (invariant, e.file, e.line)
+        }
+        None => (err.to_string(), "", 0),
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::Valid;
+use crate::Validate;
+use crate::ValidationError;
+use crate::event::describe;
+use crate::testing::Counted;
+
+/// Where the check of [`Counted`] is defined.
+const FIXTURE_FILE: &str = "src/testing.rs";
+
+/// Fields of an event or a record, rendered as strings.
+#[derive(Default)]
+struct Fields(BTreeMap<String, String>);
+
+impl Fields {
+    fn get(&self, key: &str) -> &str {
+        self.0.get(key).map(String::as_str).unwrap_or_default()
+    }
+
+    fn insert(&mut self, key: impl ToString, value: impl ToString) {
+        self.0.insert(key.to_string(), value.to_string());
+    }
+
+    /// Assert the fields of the failure of `Counted<N> { a: 20 }`.
+    fn assert_failure<const N: usize>(&self) {
+        assert_eq!("validit", self.get("target"));
+        assert_eq!("ERROR", self.get("level"));
+        assert_eq!(std::any::type_name::<Counted<N>>(), self.get("type_name"));
+        assert_eq!("expect: self.a(20) <= 10(10)", self.get("invariant"));
+        assert_eq!(FIXTURE_FILE, self.get("file"));
+
+        let location = format!("{}:{}", self.get("file"), self.get("line"));
+        assert_eq!(
+            format!("expect: self.a(20) <= 10(10) at {}", location),
+            self.get("error")
+        );
+    }
+}
+
+#[test]
+fn test_describe() {
+    let err = Counted::<22> { a: 20 }.validate().unwrap_err();
+    let (invariant, file, line) = describe(err.as_ref());
+    assert_eq!("expect: self.a(20) <= 10(10)", invariant);
+    assert_eq!(FIXTURE_FILE, file);
+    assert_eq!(err.downcast_ref::<ValidationError>().unwrap().line, line);
+
+    // Found in the source chain
+    let v = Valid::new(Counted::<22> { a: 20 });
+    let err = v.try_get().unwrap_err();
+    assert_eq!(
+        (
+            "expect: self.a(20) <= 10(10)".to_string(),
+            FIXTURE_FILE,
+            line
+        ),
+        describe(&err)
+    );
+
+    // Not a ValidationError
+    let err: Box<dyn Error> = "expect: a(3) <= b(2) at src/foo.rs:10".into();
+    assert_eq!(
+        ("expect: a(3) <= b(2) at src/foo.rs:10".to_string(), "", 0),
+        describe(err.as_ref())
+    );
+}
+
+#[cfg(feature = "tracing")]
+mod tracing_test {
+    use std::fmt::Debug;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+
+    use tracing::Event;
+    use tracing::Id;
+    use tracing::Metadata;
+    use tracing::field::Field;
+    use tracing::field::Visit;
+    use tracing::span::Attributes;
+    use tracing::span::Record;
+
+    use super::Fields;
+    use crate::ValidateExt;
+    use crate::testing::Counted;
+
+    /// Collect the fields of every span and event.
+    #[derive(Default)]
+    struct Collector {
+        next_id: AtomicU64,
+        spans: Arc<Mutex<Vec<(&'static str, Fields)>>>,
+        events: Arc<Mutex<Vec<Fields>>>,
+    }
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.insert(field.name(), format!("{:?}", value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.insert(field.name(), value);
+        }
+    }
+
+    impl tracing::Subscriber for Collector {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields::default();
+            span.record(&mut fields);
+            self.spans.lock().unwrap().push((span.metadata().name(), fields));
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            fields.insert("target", event.metadata().target());
+            fields.insert("level", event.metadata().level());
+            self.events.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_tracing_event() {
+        let collector = Collector::default();
+        let spans = collector.spans.clone();
+        let events = collector.events.clone();
+
+        tracing::subscriber::with_default(collector, || {
+            let f = Counted::<20> { a: 1 }.valid();
+            let _x = f.a;
+
+            assert!(Counted::<20> { a: 20 }.try_valid().is_err());
+        });
+
+        let spans = spans.lock().unwrap();
+        assert_eq!(2, spans.len());
+        for (name, fields) in spans.iter() {
+            assert_eq!("validate", *name);
+            assert_eq!(
+                std::any::type_name::<Counted<20>>(),
+                fields.get("type_name")
+            );
+        }
+
+        let events = events.lock().unwrap();
+        assert_eq!(1, events.len());
+        assert_eq!("validation failed", events[0].get("message"));
+        events[0].assert_failure::<20>();
+    }
+}
+
+#[cfg(feature = "log")]
+mod log_test {
+    use std::sync::Mutex;
+
+    use log::Log;
+    use log::Metadata;
+    use log::Record;
+    use log::kv::Key;
+    use log::kv::Value;
+    use log::kv::VisitSource;
+
+    use super::Fields;
+    use crate::ValidateExt;
+    use crate::testing::Counted;
+
+    /// A logger is process-wide, thus the records are collected in a static.
+    static RECORDS: Mutex<Vec<Fields>> = Mutex::new(Vec::new());
+
+    struct Collector;
+
+    impl<'kvs> VisitSource<'kvs> for Fields {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+            self.insert(key, value);
+            Ok(())
+        }
+    }
+
+    impl Log for Collector {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            let mut fields = Fields::default();
+            record.key_values().visit(&mut fields).unwrap();
+            fields.insert("target", record.target());
+            fields.insert("level", record.level());
+            fields.insert("message", record.args());
+            RECORDS.lock().unwrap().push(fields);
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_log_record() {
+        log::set_logger(&Collector).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        assert!(Counted::<21> { a: 20 }.try_valid().is_err());
+
+        let name = std::any::type_name::<Counted<21>>();
+        let records = RECORDS.lock().unwrap();
+        let records = records.iter().filter(|r| r.get("type_name") == name).collect::<Vec<_>>();
+        assert_eq!(1, records.len());
+
+        let r = records[0];
+        assert_eq!(
+            format!("validation failed: {}", r.get("error")),
+            r.get("message")
+        );
+        r.assert_failure::<21>();
+    }
+}
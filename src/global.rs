@@ -0,0 +1,156 @@
+//! Process-wide switch to turn validation on or off, globally or for specific types.
+//!
+//! The initial setting is loaded from environment variable `VALIDIT` when it is first used:
+//! - unset, empty or `on`: validation is on for all types;
+//! - `off`: validation is off for all types;
+//! - a comma separated list of type names, such as `Foo,my_crate::Bar`: validation is on only for
+//!   the listed types.
+//!
+//! A type name matches either the full name returned by [`std::any::type_name()`], such as
+//! `my_crate::Foo<u64>`, or the last path segment without generic arguments, such as `Foo`.
+//! A reference type such as `&my_crate::Foo` follows the setting of the type it refers to.
+//!
+//! The per-instance setting by [`Valid::enable_validation()`](crate::Valid::enable_validation)
+//! overrides the process-wide setting.
+
+use std::any::type_name;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::sync::RwLock;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+
+const UNINIT: u8 = 0;
+const ON: u8 = 1;
+const OFF: u8 = 2;
+const PER_TYPE: u8 = 3;
+
+/// A cache of the registry so that the common cases do not need to take the lock.
+static MODE: AtomicU8 = AtomicU8::new(UNINIT);
+
+static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+
+/// The process-wide setting: a default for all types and per-type overrides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Registry {
+    pub(crate) default: bool,
+    pub(crate) types: HashMap<String, bool>,
+}
+
+impl Registry {
+    /// Parse the value of environment variable `VALIDIT`.
+    pub(crate) fn parse(value: &str) -> Self {
+        let value = value.trim();
+
+        if value.is_empty() || value.eq_ignore_ascii_case("on") {
+            return Self {
+                default: true,
+                types: HashMap::new(),
+            };
+        }
+
+        if value.eq_ignore_ascii_case("off") {
+            return Self {
+                default: false,
+                types: HashMap::new(),
+            };
+        }
+
+        let types = value
+            .split(',')
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .map(|name| (name.to_string(), true))
+            .collect();
+
+        Self {
+            default: false,
+            types,
+        }
+    }
+
+    fn mode(&self) -> u8 {
+        match (self.types.is_empty(), self.default) {
+            (true, true) => ON,
+            (true, false) => OFF,
+            (false, _) => PER_TYPE,
+        }
+    }
+
+    /// Whether validation is enabled for a type with the full name `name`.
+    ///
+    /// A reference type follows the setting of the type it refers to, so that the view returned
+    /// by [`Valid::as_ref()`](crate::Valid::as_ref) is checked the same as the owner.
+    pub(crate) fn is_enabled(&self, name: &str) -> bool {
+        let name = strip_refs(name);
+
+        if let Some(enabled) = self.types.get(name) {
+            return *enabled;
+        }
+
+        if let Some(enabled) = self.types.get(short_name(name)) {
+            return *enabled;
+        }
+
+        self.default
+    }
+}
+
+/// Strip leading references from a type name: `&&mut a::Foo` -> `a::Foo`.
+fn strip_refs(mut name: &str) -> &str {
+    while let Some(stripped) = name.strip_prefix("&mut ").or_else(|| name.strip_prefix('&')) {
+        name = stripped;
+    }
+    name
+}
+
+/// Strip generic arguments and the leading path from a type name: `a::Foo<b::Bar>` -> `Foo`.
+fn short_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    REGISTRY.get_or_init(|| {
+        let registry = Registry::parse(&std::env::var("VALIDIT").unwrap_or_default());
+        MODE.store(registry.mode(), Ordering::Relaxed);
+        RwLock::new(registry)
+    })
+}
+
+fn update(f: impl FnOnce(&mut Registry)) {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    f(&mut registry);
+    MODE.store(registry.mode(), Ordering::Relaxed);
+}
+
+/// Turn validation on or off for all types, except the ones set with [`set_type_enabled()`].
+pub fn set_enabled(enabled: bool) {
+    update(|r| r.default = enabled);
+}
+
+/// Turn validation on or off for the type named `name`.
+///
+/// `name` is either the full type name, such as `my_crate::Foo<u64>`, or the short name, such
+/// as `Foo`.
+pub fn set_type_enabled(name: impl ToString, enabled: bool) {
+    update(|r| {
+        r.types.insert(name.to_string(), enabled);
+    });
+}
+
+/// Return whether validation is enabled for type `T` by the process-wide setting.
+pub fn is_enabled<T: ?Sized>() -> bool {
+    match MODE.load(Ordering::Relaxed) {
+        ON => true,
+        OFF => false,
+        PER_TYPE => {
+            let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+            registry.is_enabled(type_name::<T>())
+        }
+        _ => {
+            registry();
+            is_enabled::<T>()
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::Valid;
+use crate::Validate;
+use crate::global::Registry;
+use crate::less_equal;
+
+#[test]
+fn test_registry_parse() {
+    let on = Registry {
+        default: true,
+        types: HashMap::new(),
+    };
+    assert_eq!(on, Registry::parse(""));
+    assert_eq!(on, Registry::parse("on"));
+    assert_eq!(on, Registry::parse(" ON "));
+
+    let off = Registry {
+        default: false,
+        types: HashMap::new(),
+    };
+    assert_eq!(off, Registry::parse("off"));
+    assert_eq!(off, Registry::parse("Off"));
+
+    let listed = Registry {
+        default: false,
+        types: HashMap::from([("Foo".to_string(), true), ("a::Bar".to_string(), true)]),
+    };
+    assert_eq!(listed, Registry::parse("Foo, a::Bar,"));
+}
+
+#[test]
+fn test_registry_is_enabled() {
+    let r = Registry::parse("Foo,a::Bar<u64>");
+
+    assert!(r.is_enabled("Foo"));
+    assert!(r.is_enabled("x::Foo"));
+    assert!(r.is_enabled("x::y::Foo<z::Bar>"));
+    assert!(r.is_enabled("a::Bar<u64>"));
+
+    assert!(!r.is_enabled("a::Bar<u32>"));
+    assert!(!r.is_enabled("Bar"));
+    assert!(!r.is_enabled("x::FooBar"));
+
+    // A reference follows the type it refers to.
+    assert!(r.is_enabled("&x::Foo"));
+    assert!(r.is_enabled("&mut &a::Bar<u64>"));
+    assert!(!r.is_enabled("&a::Bar<u32>"));
+
+    let mut r = Registry::parse("on");
+    assert!(r.is_enabled("x::Foo"));
+
+    r.types.insert("Foo".to_string(), false);
+    assert!(!r.is_enabled("x::Foo"));
+    assert!(r.is_enabled("x::Bar"));
+}
+
+/// A type used only by this test, so that setting it does not affect other tests.
+struct GlobalSwitched {
+    le_10: u64,
+}
+
+impl Validate for GlobalSwitched {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        less_equal!(self.le_10, 10);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_set_type_enabled() {
+    assert!(crate::is_enabled::<GlobalSwitched>());
+
+    crate::set_type_enabled("GlobalSwitched", false);
+    assert!(!crate::is_enabled::<GlobalSwitched>());
+    assert!(crate::is_enabled::<u64>());
+
+    // Disabled by the process-wide setting
+    let f = Valid::new(GlobalSwitched { le_10: 20 });
+    assert!(!f.is_enabled());
+    let _x = f.le_10;
+
+    // The per-instance setting overrides the process-wide setting
+    let res = std::panic::catch_unwind(|| {
+        let mut f = Valid::new(GlobalSwitched { le_10: 20 });
+        f.enable_validation(true);
+        let _x = f.le_10;
+    });
+    assert!(res.is_err());
+
+    crate::set_type_enabled(std::any::type_name::<GlobalSwitched>(), true);
+    assert!(crate::is_enabled::<GlobalSwitched>());
+
+    let res = std::panic::catch_unwind(|| {
+        let f = Valid::new(GlobalSwitched { le_10: 20 });
+        let _x = f.le_10;
+    });
+    assert!(res.is_err());
+}
+
+/// A type used only by this test, so that setting it does not affect other tests.
+struct GlobalSwitchedRef {
+    le_10: u64,
+}
+
+impl Validate for GlobalSwitchedRef {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        less_equal!(self.le_10, 10);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_set_type_enabled_as_ref() {
+    crate::set_type_enabled("GlobalSwitchedRef", false);
+    assert!(!crate::is_enabled::<&GlobalSwitchedRef>());
+
+    // The view returned by `as_ref()` follows the setting of the referent type.
+    let f = Valid::new(GlobalSwitchedRef { le_10: 20 });
+    assert!(!f.as_ref().is_enabled());
+    let _x = f.as_ref().le_10;
+
+    crate::set_type_enabled(std::any::type_name::<GlobalSwitchedRef>(), true);
+    assert!(crate::is_enabled::<&GlobalSwitchedRef>());
+
+    let f = Valid::new(GlobalSwitchedRef { le_10: 5 });
+    assert!(f.as_ref().is_enabled());
+}
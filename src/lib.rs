@@ -35,21 +35,93 @@
 
 #![cfg_attr(feature = "bench", feature(test))]
 
+/// Whether validation is compiled in.
+///
+/// It is `true` when `debug_assertions` is on, or feature `release-checks` is enabled. Otherwise
+/// [`Valid`] never validates, and with feature `strip` it does not keep any per-instance state of
+/// validation.
+pub const CHECKS_COMPILED: bool = cfg!(any(debug_assertions, feature = "release-checks"));
+
 #[cfg(feature = "macros")]
 pub mod macros;
 #[cfg(feature = "macros")]
 #[cfg(test)]
 mod macros_test;
 
+mod access_error;
 #[cfg(feature = "bench")]
 mod bench;
+#[cfg(feature = "dirty-tracking")]
+mod clean;
+#[cfg(feature = "dirty-tracking")]
+#[cfg(test)]
+mod clean_test;
+#[cfg(any(feature = "tracing", feature = "log"))]
+pub mod event;
+#[cfg(any(feature = "tracing", feature = "log"))]
+#[cfg(test)]
+mod event_test;
+pub mod global;
+#[cfg(test)]
+mod global_test;
+pub mod policy;
+#[cfg(test)]
+mod policy_test;
+mod proven;
+#[cfg(test)]
+mod proven_test;
+mod sampling;
+#[cfg(test)]
+mod sampling_test;
+#[cfg(feature = "serde")]
+pub mod serde_validate;
+#[cfg(feature = "serde")]
+#[cfg(test)]
+mod serde_validate_test;
+mod snapshot;
+#[cfg(test)]
+mod snapshot_test;
+mod state;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "stats")]
+#[cfg(test)]
+mod stats_test;
+pub mod sync;
+#[cfg(test)]
+mod sync_test;
+#[cfg(test)]
+mod testing;
 mod valid;
+mod valid_ops;
 #[cfg(test)]
 mod valid_test;
 mod validate;
 mod validate_ext;
 mod validate_impl;
+mod validation_error;
+#[cfg(test)]
+mod validation_error_test;
+mod write_guard;
+#[cfg(test)]
+mod write_guard_test;
 
+pub use access_error::AccessError;
+pub use access_error::AccessKind;
+pub use global::is_enabled;
+pub use global::set_enabled;
+pub use global::set_type_enabled;
+pub use policy::FailurePolicy;
+pub use proven::Proven;
+pub use sampling::Sampling;
 pub use valid::Valid;
 pub use validate::Validate;
 pub use validate_ext::ValidateExt;
+pub use validation_error::OpKind;
+pub use validation_error::Operand;
+pub use validation_error::ValidationError;
+#[cfg(feature = "derive")]
+pub use validit_derive::Validate;
+#[cfg(feature = "derive")]
+pub use validit_derive::invariant;
+pub use write_guard::WriteGuard;
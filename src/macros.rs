@@ -1,11 +1,25 @@
 //! Defines macros for validation check, such as `less!(smaller, greater)`.
 
+use std::error::Error;
 use std::fmt::Arguments;
 
+use crate::ValidationError;
+
 pub fn make_err(fmt: Arguments) -> anyerror::AnyError {
     anyerror::AnyError::error(format!("{fmt}"))
 }
 
+/// Prepend the name of an invariant to an error.
+///
+/// A [`ValidationError`] keeps the name in [`ValidationError::invariant`], other errors are
+/// converted to a message.
+pub fn with_invariant(name: &str, err: Box<dyn Error>) -> Box<dyn Error> {
+    match err.downcast::<ValidationError>() {
+        Ok(e) => Box::new(e.with_invariant(name)),
+        Err(err) => Box::new(make_err(format_args!("{}: {}", name, err))),
+    }
+}
+
 /// Assert that function call `call(a,b,...)`(up to 8 arguments) to return true, otherwise it return
 /// an error.
 ///
@@ -42,11 +56,13 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}() at {}:{}",
-                stringify!($($call).+),
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec::Vec::new(),
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 
@@ -57,13 +73,15 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}({}({:?})) at {}:{}",
-                stringify!($($call).+),
-                stringify!($a),
-                __a,
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &__a),
+                ],
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 
@@ -75,15 +93,16 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}({}({:?}), {}({:?})) at {}:{}",
-                stringify!($($call).+),
-                stringify!($a),
-                __a,
-                stringify!($b),
-                __b,
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &__a),
+                    $crate::Operand::new(stringify!($b), &__b),
+                ],
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 
@@ -96,17 +115,17 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}({}({:?}), {}({:?}), {}({:?})) at {}:{}",
-                stringify!($($call).+),
-                stringify!($a),
-                __a,
-                stringify!($b),
-                __b,
-                stringify!($c),
-                __c,
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &__a),
+                    $crate::Operand::new(stringify!($b), &__b),
+                    $crate::Operand::new(stringify!($c), &__c),
+                ],
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 
@@ -120,19 +139,18 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}({}({:?}), {}({:?}), {}({:?}), {}({:?})) at {}:{}",
-                stringify!($($call).+),
-                stringify!($a),
-                __a,
-                stringify!($b),
-                __b,
-                stringify!($c),
-                __c,
-                stringify!($d),
-                __d,
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &__a),
+                    $crate::Operand::new(stringify!($b), &__b),
+                    $crate::Operand::new(stringify!($c), &__c),
+                    $crate::Operand::new(stringify!($d), &__d),
+                ],
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 
@@ -147,21 +165,19 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}({}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?})) at {}:{}",
-                stringify!($($call).+),
-                stringify!($a),
-                __a,
-                stringify!($b),
-                __b,
-                stringify!($c),
-                __c,
-                stringify!($d),
-                __d,
-                stringify!($e),
-                __e,
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &__a),
+                    $crate::Operand::new(stringify!($b), &__b),
+                    $crate::Operand::new(stringify!($c), &__c),
+                    $crate::Operand::new(stringify!($d), &__d),
+                    $crate::Operand::new(stringify!($e), &__e),
+                ],
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 
@@ -177,23 +193,20 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}({}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?})) at {}:{}",
-                stringify!($($call).+),
-                stringify!($a),
-                __a,
-                stringify!($b),
-                __b,
-                stringify!($c),
-                __c,
-                stringify!($d),
-                __d,
-                stringify!($e),
-                __e,
-                stringify!($f),
-                __f,
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &__a),
+                    $crate::Operand::new(stringify!($b), &__b),
+                    $crate::Operand::new(stringify!($c), &__c),
+                    $crate::Operand::new(stringify!($d), &__d),
+                    $crate::Operand::new(stringify!($e), &__e),
+                    $crate::Operand::new(stringify!($f), &__f),
+                ],
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 
@@ -210,25 +223,21 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}({}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?})) at {}:{}",
-                stringify!($($call).+),
-                stringify!($a),
-                __a,
-                stringify!($b),
-                __b,
-                stringify!($c),
-                __c,
-                stringify!($d),
-                __d,
-                stringify!($e),
-                __e,
-                stringify!($f),
-                __f,
-                stringify!($g),
-                __g,
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &__a),
+                    $crate::Operand::new(stringify!($b), &__b),
+                    $crate::Operand::new(stringify!($c), &__c),
+                    $crate::Operand::new(stringify!($d), &__d),
+                    $crate::Operand::new(stringify!($e), &__e),
+                    $crate::Operand::new(stringify!($f), &__f),
+                    $crate::Operand::new(stringify!($g), &__g),
+                ],
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 
@@ -246,27 +255,22 @@ macro_rules! be_true {
         if __result {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect to be true: {}({}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?}), {}({:?})) at {}:{}",
-                stringify!($($call).+),
-                stringify!($a),
-                __a,
-                stringify!($b),
-                __b,
-                stringify!($c),
-                __c,
-                stringify!($d),
-                __d,
-                stringify!($e),
-                __e,
-                stringify!($f),
-                __f,
-                stringify!($g),
-                __g,
-                stringify!($h),
-                __h,
-                file!(), line!(),
-            )))?;
+            Err($crate::ValidationError::new(
+                $crate::OpKind::BeTrue(stringify!($($call).+)),
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &__a),
+                    $crate::Operand::new(stringify!($b), &__b),
+                    $crate::Operand::new(stringify!($c), &__c),
+                    $crate::Operand::new(stringify!($d), &__d),
+                    $crate::Operand::new(stringify!($e), &__e),
+                    $crate::Operand::new(stringify!($f), &__f),
+                    $crate::Operand::new(stringify!($g), &__g),
+                    $crate::Operand::new(stringify!($h), &__h),
+                ],
+                file!(),
+                line!(),
+                column!(),
+            ))?;
         }
     }};
 }
@@ -292,15 +296,16 @@ macro_rules! less {
         if (a < b) {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect: {}({:?}) < {}({:?}) at {}:{}",
-                stringify!($a),
-                a,
-                stringify!($b),
-                b,
+            Err($crate::ValidationError::new(
+                $crate::OpKind::Less,
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &a),
+                    $crate::Operand::new(stringify!($b), &b),
+                ],
                 file!(),
                 line!(),
-            )))?;
+                column!(),
+            ))?;
         }
     }};
 }
@@ -326,15 +331,16 @@ macro_rules! greater {
         if (a > b) {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect: {}({:?}) > {}({:?}) at {}:{}",
-                stringify!($a),
-                a,
-                stringify!($b),
-                b,
+            Err($crate::ValidationError::new(
+                $crate::OpKind::Greater,
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &a),
+                    $crate::Operand::new(stringify!($b), &b),
+                ],
                 file!(),
                 line!(),
-            )))?;
+                column!(),
+            ))?;
         }
     }};
 }
@@ -360,15 +366,16 @@ macro_rules! less_equal {
         if (a <= b) {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect: {}({:?}) <= {}({:?}) at {}:{}",
-                stringify!($a),
-                a,
-                stringify!($b),
-                b,
+            Err($crate::ValidationError::new(
+                $crate::OpKind::LessEqual,
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &a),
+                    $crate::Operand::new(stringify!($b), &b),
+                ],
                 file!(),
                 line!(),
-            )))?;
+                column!(),
+            ))?;
         }
     }};
 }
@@ -394,15 +401,16 @@ macro_rules! greater_equal {
         if (a >= b) {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect: {}({:?}) >= {}({:?}) at {}:{}",
-                stringify!($a),
-                a,
-                stringify!($b),
-                b,
+            Err($crate::ValidationError::new(
+                $crate::OpKind::GreaterEqual,
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &a),
+                    $crate::Operand::new(stringify!($b), &b),
+                ],
                 file!(),
                 line!(),
-            )))?;
+                column!(),
+            ))?;
         }
     }};
 }
@@ -428,15 +436,16 @@ macro_rules! equal {
         if (a == b) {
             // Ok
         } else {
-            Err($crate::macros::make_err(format_args!(
-                "expect: {}({:?}) == {}({:?}) at {}:{}",
-                stringify!($a),
-                a,
-                stringify!($b),
-                b,
+            Err($crate::ValidationError::new(
+                $crate::OpKind::Equal,
+                ::std::vec![
+                    $crate::Operand::new(stringify!($a), &a),
+                    $crate::Operand::new(stringify!($b), &b),
+                ],
                 file!(),
                 line!(),
-            )))?;
+                column!(),
+            ))?;
         }
     }};
 }
@@ -0,0 +1,108 @@
+//! Defines what to do when [`Valid<T, P>`](crate::Valid) finds an invalid state.
+//!
+//! The policy is the second type parameter of `Valid`, and defaults to [`Panic`]:
+//! ```
+//! # use std::error::Error;
+//! # use validit::Valid;
+//! # use validit::Validate;
+//! # use validit::policy::LogAndContinue;
+//! struct Foo { a: u64 }
+//! impl Validate for Foo {
+//!     fn validate(&self) -> Result<(), Box<dyn Error>> {
+//!         validit::less_equal!(self.a, 10);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let f: Valid<Foo, LogAndContinue> = Valid::with_policy(Foo { a: 20 }, LogAndContinue);
+//! let _x = f.a; // print "invalid state: expect: self.a(20) <= 10(10) ..." to stderr
+//! ```
+
+use std::error::Error;
+
+pub use crate::sampling::Sampled;
+pub use crate::snapshot::Snapshot;
+
+/// Defines how to handle an invalid state found when accessing a [`Valid<T>`](crate::Valid).
+///
+/// It is generic over the type `T` of the value, so that a policy can look at the value, such as
+/// [`Snapshot`], which keeps the last valid state. A policy that does not need the value
+/// implements it for every `T: ?Sized`.
+pub trait FailurePolicy<T: ?Sized> {
+    /// Called with the error when an invalid state is found.
+    ///
+    /// The error is an [`AccessError`](crate::AccessError), which tells where the invalid state is
+    /// detected and keeps the error returned by
+    /// [`Validate::validate()`](crate::Validate::validate) as its source. Both can be got back with
+    /// `downcast_ref()`.
+    ///
+    /// If it returns, the access goes on with the invalid state.
+    fn on_failure(&self, err: &(dyn Error + 'static));
+
+    /// Return whether to validate this access.
+    ///
+    /// By default every access is validated. [`Sampled`] validates only a sample of them.
+    fn should_validate(&self) -> bool {
+        true
+    }
+
+    /// Called with the value every time it passes validation.
+    fn on_valid(&self, inner: &T) {
+        let _ = inner;
+    }
+
+    /// Return more information about an invalid value, which is kept in the
+    /// [`AccessError`](crate::AccessError) passed to [`FailurePolicy::on_failure()`].
+    fn context(&self, inner: &T) -> Option<String> {
+        let _ = inner;
+        None
+    }
+}
+
+/// Panic when an invalid state is found. This is the default policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Panic;
+
+impl<T: ?Sized> FailurePolicy<T> for Panic {
+    /// The panic is located at the access to the `Valid`, such as a `Deref`.
+    #[track_caller]
+    fn on_failure(&self, err: &(dyn Error + 'static)) {
+        panic!("invalid state: {}", err);
+    }
+}
+
+/// Print the error to stderr and abort the process when an invalid state is found.
+///
+/// Unlike [`Panic`], it can not be caught, and it does not unwind through code that is not
+/// prepared for a broken invariant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Abort;
+
+impl<T: ?Sized> FailurePolicy<T> for Abort {
+    fn on_failure(&self, err: &(dyn Error + 'static)) {
+        eprintln!("invalid state: {}", err);
+        std::process::abort();
+    }
+}
+
+/// Print the error to stderr and go on accessing the invalid state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LogAndContinue;
+
+impl<T: ?Sized> FailurePolicy<T> for LogAndContinue {
+    fn on_failure(&self, err: &(dyn Error + 'static)) {
+        eprintln!("invalid state: {}", err);
+    }
+}
+
+/// Call a function with the error when an invalid state is found.
+///
+/// If the function returns, the access goes on with the invalid state.
+#[derive(Debug, Clone, Copy)]
+pub struct Callback(pub fn(&(dyn Error + 'static)));
+
+impl<T: ?Sized> FailurePolicy<T> for Callback {
+    fn on_failure(&self, err: &(dyn Error + 'static)) {
+        (self.0)(err)
+    }
+}
@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::sync::Mutex;
+
+use crate::AccessError;
+use crate::AccessKind;
+use crate::OpKind;
+use crate::Valid;
+use crate::ValidationError;
+use crate::policy::Callback;
+use crate::policy::LogAndContinue;
+use crate::policy::Panic;
+use crate::testing::Foo;
+
+#[test]
+fn test_policy_panic() {
+    let res = std::panic::catch_unwind(|| {
+        let f = Valid::with_policy(Foo { le_10: 20 }, Panic);
+        let _x = f.le_10;
+    });
+    let err = res.unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert!(msg.starts_with("invalid state: expect: self.le_10(20) <= 10(10) at "));
+}
+
+#[test]
+#[allow(clippy::clone_on_copy)]
+fn test_policy_log_and_continue() {
+    let mut f = Valid::with_policy(Foo { le_10: 20 }, LogAndContinue);
+    assert_eq!(20, f.le_10);
+
+    f.le_10 += 1;
+    assert_eq!(21, f.le_10);
+
+    let _c = f.clone();
+    let _r = f.as_ref();
+}
+
+#[test]
+fn test_policy_callback() {
+    static ERRORS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static KINDS: Mutex<Vec<AccessKind>> = Mutex::new(Vec::new());
+
+    fn on_failure(err: &(dyn Error + 'static)) {
+        // The policy gets an `AccessError` with the error from `validate()` as its source.
+        let access = err.downcast_ref::<AccessError>().unwrap();
+        let source = access.source().unwrap().downcast_ref::<ValidationError>().unwrap();
+        assert_eq!(OpKind::LessEqual, source.op);
+
+        KINDS.lock().unwrap().push(access.kind());
+        ERRORS.lock().unwrap().push(source.to_string());
+    }
+
+    let f = Valid::with_policy(Foo { le_10: 5 }, Callback(on_failure));
+    let _x = f.le_10;
+    assert!(ERRORS.lock().unwrap().is_empty());
+
+    let mut f = Valid::with_policy(Foo { le_10: 20 }, Callback(on_failure));
+    let _x = f.le_10;
+    {
+        let errors = ERRORS.lock().unwrap();
+        assert_eq!(1, errors.len());
+        assert!(errors[0].starts_with("expect: self.le_10(20) <= 10(10) at "));
+    }
+
+    // `write()` validates before and after writing
+    f.write().le_10 = 30;
+    {
+        let errors = ERRORS.lock().unwrap();
+        assert_eq!(3, errors.len());
+        assert!(errors[1].starts_with("expect: self.le_10(20) <= 10(10) at "));
+        assert!(errors[2].starts_with("expect: self.le_10(30) <= 10(10) at "));
+    }
+    assert_eq!(
+        vec![
+            AccessKind::Access,
+            AccessKind::Access,
+            AccessKind::AfterWrite
+        ],
+        *KINDS.lock().unwrap()
+    );
+}
+
+#[test]
+fn test_policy_default_layout() {
+    // Features `dirty-tracking` and `track-mutation` add per-instance state.
+    #[cfg(not(any(feature = "dirty-tracking", feature = "track-mutation")))]
+    assert_eq!(
+        size_of::<(bool, Foo)>(),
+        size_of::<Valid<Foo>>(),
+        "default policy adds nothing to the layout"
+    );
+    assert_eq!(0, size_of::<Panic>());
+    assert_eq!(
+        size_of::<Valid<Foo, LogAndContinue>>(),
+        size_of::<Valid<Foo>>(),
+    );
+    assert_eq!(size_of::<Valid<Foo>>(), size_of::<Valid<Foo, Panic>>());
+}
@@ -0,0 +1,121 @@
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::ops::Deref;
+
+use crate::Valid;
+use crate::Validate;
+use crate::validate;
+
+/// An immutable value that is validated once when it is built.
+///
+/// Unlike [`Valid<T>`], it can only be built with a validating constructor, and it does not
+/// provide a mutable reference to the value, thus it is always valid and reading it does not
+/// validate it again. It is suitable for a value that does not change after it is built, such as
+/// a config or a message.
+///
+/// The state is always validated when building a `Proven`, regardless of build profile or the
+/// validation setting.
+///
+/// ```
+/// # use std::error::Error;
+/// # use validit::Proven;
+/// # use validit::Validate;
+/// struct Config { timeout_ms: u64 }
+/// impl Validate for Config {
+///     fn validate(&self) -> Result<(), Box<dyn Error>> {
+///         validit::greater!(self.timeout_ms, 0);
+///         Ok(())
+///     }
+/// }
+///
+/// let c = Proven::new(Config { timeout_ms: 100 }).unwrap();
+/// assert_eq!(100, c.timeout_ms);
+///
+/// assert!(Proven::new(Config { timeout_ms: 0 }).is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Proven<T>
+where T: Validate
+{
+    inner: T,
+}
+
+impl<T> Proven<T>
+where T: Validate
+{
+    /// Build a `Proven<T>` if `inner` is valid, otherwise return the error from
+    /// [`Validate::validate()`].
+    pub fn new(inner: T) -> Result<Self, Box<dyn Error>> {
+        validate::run(&inner)?;
+        Ok(Self { inner })
+    }
+
+    /// Consume self and return the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Deref for Proven<T>
+where T: Validate
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> AsRef<T> for Proven<T>
+where T: Validate
+{
+    fn as_ref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> Borrow<T> for Proven<T>
+where T: Validate
+{
+    fn borrow(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Debug> Debug for Proven<T>
+where T: Validate
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T: Display> Display for Proven<T>
+where T: Validate
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+/// A `Proven<T>` is valid, thus it can be nested in another value being validated.
+impl<T> Validate for Proven<T>
+where T: Validate
+{
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+/// Unwrap a `Proven<T>` into a `Valid<T>` to mutate it.
+impl<T> From<Proven<T>> for Valid<T>
+where T: Validate
+{
+    fn from(proven: Proven<T>) -> Self {
+        Valid::new(proven.inner)
+    }
+}
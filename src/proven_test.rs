@@ -0,0 +1,40 @@
+use std::mem::size_of;
+
+use crate::Proven;
+use crate::Valid;
+use crate::testing::Foo;
+
+#[test]
+fn test_proven_new() {
+    let p = Proven::new(Foo { le_10: 5 }).unwrap();
+    assert_eq!(5, p.le_10);
+    assert_eq!(&Foo { le_10: 5 }, p.as_ref());
+    assert_eq!("Foo { le_10: 5 }", format!("{:?}", p));
+    assert_eq!(Foo { le_10: 5 }, p.into_inner());
+
+    let err = Proven::new(Foo { le_10: 20 }).unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
+}
+
+#[test]
+fn test_proven_derived_trait() {
+    let a = Proven::new(Foo { le_10: 1 }).unwrap();
+    let b = Proven::new(Foo { le_10: 2 }).unwrap();
+
+    let c = a;
+    assert_eq!(a, c);
+    assert!(a < b);
+    assert_eq!(size_of::<Foo>(), size_of::<Proven<Foo>>());
+}
+
+#[test]
+fn test_proven_into_valid() {
+    let p = Proven::new(Foo { le_10: 5 }).unwrap();
+    let mut v: Valid<Foo> = p.into();
+    v.le_10 = 20;
+
+    let res = std::panic::catch_unwind(|| {
+        let _x = v.le_10;
+    });
+    assert!(res.is_err());
+}
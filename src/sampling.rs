@@ -0,0 +1,173 @@
+//! Validate only a sample of the accesses, for types whose validation is expensive.
+
+use std::cell::Cell;
+use std::error::Error;
+use std::hash::BuildHasher;
+use std::hash::RandomState;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::FailurePolicy;
+use crate::policy::Panic;
+
+/// Defines how often the state is validated when it is accessed through a [`Valid`](crate::Valid)
+/// with policy [`Sampled`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Sampling {
+    /// Validate every access.
+    #[default]
+    Always,
+
+    /// Validate one of every `n` accesses, starting from the first one.
+    Every(u64),
+
+    /// Validate an access with probability `p`, which is in range `[0, 1]`.
+    Probability(f64),
+
+    /// Validate at most once per time interval.
+    Interval(Duration),
+}
+
+/// A [`FailurePolicy`] that validates only a sample of the accesses, and handles an invalid state
+/// with the policy `P`.
+///
+/// The sampling state is kept in the instance, thus every `Valid` is sampled on its own and
+/// accessing it does not contend with other instances.
+///
+/// ```
+/// # use std::error::Error;
+/// # use validit::Sampling;
+/// # use validit::Valid;
+/// # use validit::Validate;
+/// # use validit::policy::Sampled;
+/// struct Log { entries: Vec<u64> }
+///
+/// impl Validate for Log {
+///     fn validate(&self) -> Result<(), Box<dyn Error>> {
+///         for w in self.entries.windows(2) {
+///             validit::less!(w[0], w[1]);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let log = Valid::with_policy(Log { entries: vec![1, 2, 3] }, Sampled::new(Sampling::Every(100)));
+/// let _x = log.entries.len(); // validated
+/// let _x = log.entries.len(); // not validated
+/// ```
+#[derive(Debug, Default)]
+pub struct Sampled<P = Panic> {
+    sampling: Sampling,
+
+    /// The number of accesses, for [`Sampling::Every`].
+    count: AtomicU64,
+
+    /// Microseconds since [`epoch()`] of the last validation plus one, or `0` if never validated,
+    /// for [`Sampling::Interval`].
+    last: AtomicU64,
+
+    policy: P,
+}
+
+impl Sampled<Panic> {
+    /// Create a policy that validates a sample of the accesses and panics on an invalid state.
+    pub fn new(sampling: Sampling) -> Self {
+        Self::with_policy(sampling, Panic)
+    }
+}
+
+impl<P> Sampled<P> {
+    /// Create a policy that validates a sample of the accesses and handles an invalid state with
+    /// `policy`.
+    pub fn with_policy(sampling: Sampling, policy: P) -> Self {
+        Self {
+            sampling,
+            count: AtomicU64::new(0),
+            last: AtomicU64::new(0),
+            policy,
+        }
+    }
+
+    /// Return how the accesses are sampled.
+    pub fn sampling(&self) -> Sampling {
+        self.sampling
+    }
+}
+
+/// A clone starts sampling from scratch.
+impl<P: Clone> Clone for Sampled<P> {
+    fn clone(&self) -> Self {
+        Self::with_policy(self.sampling, self.policy.clone())
+    }
+}
+
+impl<T, P> FailurePolicy<T> for Sampled<P>
+where
+    T: ?Sized,
+    P: FailurePolicy<T>,
+{
+    #[track_caller]
+    fn on_failure(&self, err: &(dyn Error + 'static)) {
+        self.policy.on_failure(err)
+    }
+
+    fn on_valid(&self, inner: &T) {
+        self.policy.on_valid(inner)
+    }
+
+    fn context(&self, inner: &T) -> Option<String> {
+        self.policy.context(inner)
+    }
+
+    fn should_validate(&self) -> bool {
+        match self.sampling {
+            Sampling::Always => true,
+            Sampling::Probability(p) => random() < p,
+            Sampling::Every(n) => {
+                n <= 1 || self.count.fetch_add(1, Ordering::Relaxed).is_multiple_of(n)
+            }
+            Sampling::Interval(interval) => {
+                let now = micros(epoch().elapsed()).saturating_add(1);
+                let last = self.last.load(Ordering::Relaxed);
+                if last != 0 && now.saturating_sub(last) < micros(interval) {
+                    return false;
+                }
+
+                // Only one of the concurrent accesses validates.
+                self.last.compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed).is_ok()
+            }
+        }
+    }
+}
+
+/// Convert a duration to microseconds, saturating at `u64::MAX`, such as for `Duration::MAX`.
+fn micros(d: Duration) -> u64 {
+    u64::try_from(d.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// The time base of [`Sampling::Interval`].
+fn epoch() -> Instant {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    *EPOCH.get_or_init(Instant::now)
+}
+
+/// Return a pseudo random number in range `[0, 1)`.
+fn random() -> f64 {
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(RandomState::new().hash_one(0u64) | 1);
+    }
+
+    STATE.with(|state| {
+        // xorshift64
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
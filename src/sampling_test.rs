@@ -0,0 +1,85 @@
+use std::error::Error;
+use std::time::Duration;
+
+use crate::Sampling;
+use crate::Valid;
+use crate::Validate;
+use crate::policy::Sampled;
+use crate::testing::Counted;
+
+fn new<const N: usize>(sampling: Sampling) -> Valid<Counted<N>, Sampled> {
+    #[cfg_attr(not(feature = "dirty-tracking"), allow(unused_mut))]
+    let mut v = Valid::with_policy(Counted::<N> { a: 0 }, Sampled::new(sampling));
+    // Otherwise a clean value is validated only once.
+    #[cfg(feature = "dirty-tracking")]
+    v.enable_dirty_tracking(false);
+    v
+}
+
+fn access<const N: usize>(v: &Valid<Counted<N>, Sampled>, times: u64) -> u64 {
+    for _ in 0..times {
+        let _x = v.a;
+    }
+    Counted::<N>::validated()
+}
+
+#[test]
+fn test_sampling_always() {
+    assert_eq!(100, access(&new::<0>(Sampling::Always), 100));
+}
+
+#[test]
+fn test_sampling_every() {
+    // The 1st, 11th, ..., 91st accesses are validated.
+    assert_eq!(10, access(&new::<1>(Sampling::Every(10)), 100));
+}
+
+#[test]
+fn test_sampling_probability() {
+    let n = access(&new::<2>(Sampling::Probability(0.1)), 10_000);
+    assert!((500..1500).contains(&n), "got: {}", n);
+}
+
+#[test]
+fn test_sampling_interval() {
+    let v = new::<3>(Sampling::Interval(Duration::from_secs(3600)));
+    assert_eq!(1, access(&v, 100));
+}
+
+#[test]
+fn test_sampling_long_interval() {
+    // 2^64 microseconds, which would wrap to 0 if truncated to `u64`.
+    let v = new::<5>(Sampling::Interval(Duration::new(
+        18_446_744_073_709,
+        551_616_000,
+    )));
+    assert_eq!(1, access(&v, 100));
+}
+
+#[test]
+fn test_sampling_per_instance() {
+    let a = new::<4>(Sampling::Every(10));
+    let b = new::<4>(Sampling::Every(10));
+
+    // Each instance validates its own first access.
+    assert_eq!(1, access(&a, 5));
+    assert_eq!(2, access(&b, 5));
+
+    // A clone starts from scratch.
+    let c = a.clone();
+    assert_eq!(3, access(&c, 1));
+}
+
+#[test]
+fn test_sampling_zero_probability_never_validates() {
+    struct Invalid;
+
+    impl Validate for Invalid {
+        fn validate(&self) -> Result<(), Box<dyn Error>> {
+            Err("invalid".into())
+        }
+    }
+
+    let v = Valid::with_policy(Invalid, Sampled::new(Sampling::Probability(0.0)));
+    let _x = &*v;
+}
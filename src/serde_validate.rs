@@ -0,0 +1,111 @@
+//! Serde support, enabled by feature `serde`.
+//!
+//! [`Valid<T>`] and [`Proven<T>`] are serialized transparently as `T`. When deserializing, the
+//! value is validated with [`Validate::validate()`], and an invalid value is rejected with
+//! [`serde::de::Error::custom()`], regardless of build profile or the validation setting.
+//!
+//! For a field of a plain type `T: Validate`, this module can be used with `#[serde(with)]` to
+//! validate it when deserializing:
+//!
+//! ```
+//! # use std::error::Error;
+//! # use validit::Validate;
+//! #[derive(serde::Deserialize)]
+//! struct Snapshot {
+//!     #[serde(with = "validit::serde_validate")]
+//!     term: Term,
+//! }
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Term(u64);
+//!
+//! impl Validate for Term {
+//!     fn validate(&self) -> Result<(), Box<dyn Error>> {
+//!         validit::greater!(self.0, 0);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! assert!(serde_json::from_str::<Snapshot>(r#"{"term":1}"#).is_ok());
+//! assert!(serde_json::from_str::<Snapshot>(r#"{"term":0}"#).is_err());
+//! ```
+
+use std::ops::Deref;
+
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde::de::Error;
+use serde::ser;
+
+use crate::FailurePolicy;
+use crate::Proven;
+use crate::Valid;
+use crate::Validate;
+use crate::validate;
+
+/// Serialize a `T: Validate` field as it is.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Validate + Serialize + ?Sized,
+    S: Serializer,
+{
+    value.serialize(serializer)
+}
+
+/// Deserialize a `T: Validate` field and reject it if it is invalid.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Validate + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let value = T::deserialize(deserializer)?;
+    validate::run(&value).map_err(D::Error::custom)?;
+    Ok(value)
+}
+
+/// Serialize the wrapped value. It is an access to the value and is validated as
+/// [`Valid::try_get()`] does: an invalid value is rejected with [`serde::ser::Error::custom()`]
+/// instead of being handled by the policy.
+impl<T, P> Serialize for Valid<T, P>
+where
+    T: Validate + Serialize + ?Sized,
+    P: FailurePolicy<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        self.try_get().map_err(ser::Error::custom)?.serialize(serializer)
+    }
+}
+
+impl<'de, T, P> Deserialize<'de> for Valid<T, P>
+where
+    T: Validate + Deserialize<'de>,
+    P: FailurePolicy<T> + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let inner = deserialize(deserializer)?;
+        Ok(Valid::with_policy(inner, P::default()))
+    }
+}
+
+impl<T> Serialize for Proven<T>
+where T: Validate + Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        self.deref().serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Proven<T>
+where T: Validate + Deserialize<'de>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let inner = T::deserialize(deserializer)?;
+        Proven::new(inner).map_err(D::Error::custom)
+    }
+}
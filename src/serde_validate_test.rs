@@ -0,0 +1,72 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Proven;
+use crate::Valid;
+use crate::policy::LogAndContinue;
+use crate::testing::Foo;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    valid: Valid<Foo>,
+    proven: Proven<Foo>,
+    #[serde(with = "crate::serde_validate")]
+    plain: Foo,
+}
+
+#[test]
+fn test_serde_transparent() -> Result<(), serde_json::Error> {
+    let v = Valid::new(Foo { le_10: 5 });
+    assert_eq!(r#"{"le_10":5}"#, serde_json::to_string(&v)?);
+
+    let p = Proven::new(Foo { le_10: 5 }).unwrap();
+    assert_eq!(r#"{"le_10":5}"#, serde_json::to_string(&p)?);
+
+    let s = Snapshot {
+        valid: Valid::new(Foo { le_10: 1 }),
+        proven: Proven::new(Foo { le_10: 2 }).unwrap(),
+        plain: Foo { le_10: 3 },
+    };
+    let got = serde_json::to_string(&s)?;
+    assert_eq!(
+        r#"{"valid":{"le_10":1},"proven":{"le_10":2},"plain":{"le_10":3}}"#,
+        got
+    );
+
+    let s: Snapshot = serde_json::from_str(&got)?;
+    assert_eq!(1, s.valid.le_10);
+    assert_eq!(2, s.proven.le_10);
+    assert_eq!(3, s.plain.le_10);
+
+    let v: Valid<Foo, LogAndContinue> = serde_json::from_str(r#"{"le_10":5}"#)?;
+    assert_eq!(5, v.le_10);
+
+    Ok(())
+}
+
+#[test]
+fn test_serde_reject_invalid() {
+    let err = serde_json::from_str::<Valid<Foo>>(r#"{"le_10":20}"#).unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
+
+    let err = serde_json::from_str::<Proven<Foo>>(r#"{"le_10":20}"#).unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
+
+    let invalid = [
+        r#"{"valid":{"le_10":20},"proven":{"le_10":2},"plain":{"le_10":3}}"#,
+        r#"{"valid":{"le_10":1},"proven":{"le_10":20},"plain":{"le_10":3}}"#,
+        r#"{"valid":{"le_10":1},"proven":{"le_10":2},"plain":{"le_10":20}}"#,
+    ];
+    for s in invalid {
+        assert!(serde_json::from_str::<Snapshot>(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn test_serde_serialize_invalid() {
+    // `Valid::new()` does not validate.
+    let v = Valid::new(Foo { le_10: 20 });
+
+    let err = serde_json::to_string(&v).unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
+}
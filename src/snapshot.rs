@@ -0,0 +1,204 @@
+//! Keep the last valid state of a value and show what is changed when it becomes invalid.
+
+use std::error::Error;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use crate::FailurePolicy;
+use crate::policy::Panic;
+
+/// A [`FailurePolicy`] that keeps a clone of the value every time it passes validation, and when
+/// the validation fails, appends a line-by-line diff between the `{:#?}` rendering of the last
+/// valid state and the current one to the error. The error is then handled by the policy `P`.
+///
+/// ```
+/// # use std::error::Error;
+/// # use validit::Valid;
+/// # use validit::Validate;
+/// # use validit::policy::Snapshot;
+/// #[derive(Debug, Clone)]
+/// struct Foo { a: u64, b: u64 }
+/// impl Validate for Foo {
+///     fn validate(&self) -> Result<(), Box<dyn Error>> {
+///         validit::less_equal!(self.a, 10);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut f = Valid::with_policy(Foo { a: 5, b: 1 }, Snapshot::new());
+/// let _x = f.a;
+///
+/// f.a = 20;
+/// let err = f.try_get().unwrap_err().to_string();
+/// assert!(err.contains("diff from the last valid state:
+///   Foo {
+/// -     a: 5,
+/// +     a: 20,
+///       b: 1,
+///   }"));
+/// ```
+///
+/// It is expensive because the value is cloned on every validation, use it only for debugging.
+#[derive(Debug)]
+pub struct Snapshot<T, P = Panic> {
+    /// The value when it passes validation the last time.
+    last: Mutex<Option<T>>,
+
+    policy: P,
+}
+
+impl<T> Snapshot<T, Panic> {
+    /// Create a policy that keeps the last valid state and panics on an invalid state.
+    pub fn new() -> Self {
+        Self::with_policy(Panic)
+    }
+}
+
+impl<T, P> Snapshot<T, P> {
+    /// Create a policy that keeps the last valid state and handles an invalid state with
+    /// `policy`.
+    pub fn with_policy(policy: P) -> Self {
+        Self {
+            last: Mutex::new(None),
+            policy,
+        }
+    }
+}
+
+impl<T, P: Default> Default for Snapshot<T, P> {
+    fn default() -> Self {
+        Self::with_policy(P::default())
+    }
+}
+
+/// A clone keeps the last valid state, such as the view returned by
+/// [`Valid::as_ref()`](crate::Valid::as_ref).
+impl<T: Clone, P: Clone> Clone for Snapshot<T, P> {
+    fn clone(&self) -> Self {
+        Self {
+            last: Mutex::new(self.lock().clone()),
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+impl<T, P> Snapshot<T, P> {
+    fn lock(&self) -> MutexGuard<'_, Option<T>> {
+        self.last.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Keep a clone of a valid value.
+    fn store(&self, inner: &T)
+    where T: Clone {
+        *self.lock() = Some(inner.clone());
+    }
+
+    /// Render the diff from the last valid state to `inner`.
+    fn diff_from_last(&self, inner: &T) -> Option<String>
+    where T: Debug {
+        self.lock().as_ref().map(|last| {
+            format!(
+                "diff from the last valid state:\n{}",
+                diff(&format!("{:#?}", last), &format!("{:#?}", inner))
+            )
+        })
+    }
+}
+
+impl<T, P> FailurePolicy<T> for Snapshot<T, P>
+where
+    T: Clone + Debug,
+    P: FailurePolicy<T>,
+{
+    #[track_caller]
+    fn on_failure(&self, err: &(dyn Error + 'static)) {
+        self.policy.on_failure(err)
+    }
+
+    fn should_validate(&self) -> bool {
+        self.policy.should_validate()
+    }
+
+    fn on_valid(&self, inner: &T) {
+        self.store(inner);
+        self.policy.on_valid(inner)
+    }
+
+    fn context(&self, inner: &T) -> Option<String> {
+        join(self.diff_from_last(inner), self.policy.context(inner))
+    }
+}
+
+/// For the view `Valid<&T, _>` returned by [`Valid::as_ref()`](crate::Valid::as_ref), which
+/// compares the referred value with the last valid state.
+impl<'a, T, P> FailurePolicy<&'a T> for Snapshot<T, P>
+where
+    T: Clone + Debug,
+    P: FailurePolicy<&'a T>,
+{
+    #[track_caller]
+    fn on_failure(&self, err: &(dyn Error + 'static)) {
+        self.policy.on_failure(err)
+    }
+
+    fn should_validate(&self) -> bool {
+        self.policy.should_validate()
+    }
+
+    fn on_valid(&self, inner: &&'a T) {
+        self.store(inner);
+        self.policy.on_valid(inner)
+    }
+
+    fn context(&self, inner: &&'a T) -> Option<String> {
+        join(self.diff_from_last(inner), self.policy.context(inner))
+    }
+}
+
+fn join(diff: Option<String>, context: Option<String>) -> Option<String> {
+    match (diff, context) {
+        (Some(diff), Some(context)) => Some(format!("{}\n{}", diff, context)),
+        (diff, context) => diff.or(context),
+    }
+}
+
+/// Build a line-by-line diff: a removed line is prefixed with `- `, an added line with `+ `, and
+/// an unchanged line with two spaces.
+pub(crate) fn diff(old: &str, new: &str) -> String {
+    let a = old.lines().collect::<Vec<_>>();
+    let b = new.lines().collect::<Vec<_>>();
+
+    // lcs[i][j] is the length of the longest common subsequence of a[i..] and b[j..].
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(a.len().max(b.len()));
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            lines.push(format!("  {}", a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(format!("- {}", a[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", b[j]));
+            j += 1;
+        }
+    }
+    lines.extend(a[i..].iter().map(|l| format!("- {}", l)));
+    lines.extend(b[j..].iter().map(|l| format!("+ {}", l)));
+
+    lines.join("\n")
+}
@@ -0,0 +1,148 @@
+use std::cell::Cell;
+use std::error::Error;
+
+use crate::Valid;
+use crate::Validate;
+use crate::less_equal;
+use crate::policy::LogAndContinue;
+use crate::policy::Snapshot;
+use crate::snapshot::diff;
+use crate::testing::Foo;
+
+#[test]
+fn test_diff() {
+    assert_eq!("", diff("", ""));
+    assert_eq!("  a\n  b", diff("a\nb", "a\nb"));
+    assert_eq!("  a\n- b\n+ x\n  c", diff("a\nb\nc", "a\nx\nc"));
+    assert_eq!("- a\n  b\n+ c", diff("a\nb", "b\nc"));
+    assert_eq!("+ a\n+ b", diff("", "a\nb"));
+}
+
+#[test]
+fn test_snapshot_diff_on_failure() {
+    let mut v = Valid::with_policy(Foo { le_10: 5 }, Snapshot::new());
+    let _x = v.le_10;
+
+    v.le_10 = 20;
+    let err = v.try_get().unwrap_err().to_string();
+
+    let want = "
+diff from the last valid state:
+  Foo {
+-     le_10: 5,
++     le_10: 20,
+  }";
+    assert!(
+        err.starts_with("expect: self.le_10(20) <= 10(10) at "),
+        "got: {}",
+        err
+    );
+    assert!(err.contains(want), "got: {}", err);
+}
+
+#[test]
+fn test_snapshot_updated_by_validation() {
+    let mut v = Valid::with_policy(Foo { le_10: 5 }, Snapshot::new());
+    let _x = v.le_10;
+
+    v.le_10 = 6;
+    let _x = v.le_10;
+
+    v.le_10 = 20;
+    let err = v.try_get().unwrap_err().to_string();
+    assert!(
+        err.contains("-     le_10: 6,\n+     le_10: 20,"),
+        "got: {}",
+        err
+    );
+}
+
+#[test]
+fn test_snapshot_without_last_valid_state() {
+    // No valid state is seen, no diff
+    let v = Valid::with_policy(Foo { le_10: 20 }, Snapshot::new());
+    let err = v.try_get().unwrap_err().to_string();
+    assert!(!err.contains("diff"), "got: {}", err);
+}
+
+#[test]
+fn test_snapshot_per_instance() {
+    let mut v = Valid::with_policy(Foo { le_10: 5 }, Snapshot::new());
+    let _x = v.le_10;
+
+    // Another instance at another address does not share the snapshot.
+    let w = Valid::with_policy(Foo { le_10: 20 }, Snapshot::new());
+    let err = w.try_get().unwrap_err().to_string();
+    assert!(!err.contains("diff"), "got: {}", err);
+
+    // Moving the instance keeps its snapshot.
+    v.le_10 = 20;
+    let moved = Box::new(v);
+    let err = moved.try_get().unwrap_err().to_string();
+    assert!(
+        err.contains("-     le_10: 5,\n+     le_10: 20,"),
+        "got: {}",
+        err
+    );
+}
+
+#[test]
+fn test_snapshot_with_policy() {
+    let mut v: Valid<Foo, Snapshot<Foo, LogAndContinue>> =
+        Valid::with_policy(Foo { le_10: 5 }, Snapshot::with_policy(LogAndContinue));
+    let _x = v.le_10;
+
+    v.le_10 = 20;
+    // LogAndContinue does not panic
+    assert_eq!(20, v.le_10);
+}
+
+#[test]
+fn test_snapshot_updated_by_modify() {
+    let mut v = Valid::with_policy(Foo { le_10: 5 }, Snapshot::new());
+
+    // Only `modify()` validates.
+    v.enable_validation(false);
+    v.modify(|f| f.le_10 = 6).unwrap();
+    v.le_10 = 20;
+    v.enable_validation(true);
+
+    let err = v.try_get().unwrap_err().to_string();
+    assert!(
+        err.contains("-     le_10: 6,\n+     le_10: 20,"),
+        "got: {}",
+        err
+    );
+}
+
+#[test]
+fn test_snapshot_as_ref() {
+    /// A value that can be changed through the view returned by `as_ref()`.
+    #[derive(Debug, Clone)]
+    struct Shared {
+        a: Cell<u64>,
+    }
+
+    impl Validate for Shared {
+        fn validate(&self) -> Result<(), Box<dyn Error>> {
+            less_equal!(self.a.get(), 10);
+            Ok(())
+        }
+    }
+
+    let v = Valid::with_policy(Shared { a: Cell::new(5) }, Snapshot::new());
+
+    // The view starts with the last valid state of `v`.
+    #[cfg_attr(not(feature = "dirty-tracking"), allow(unused_mut))]
+    let mut r = v.as_ref();
+    #[cfg(feature = "dirty-tracking")]
+    r.enable_dirty_tracking(false);
+
+    r.a.set(20);
+    let err = r.try_get().unwrap_err().to_string();
+    assert!(
+        err.contains("-         value: 5,\n+         value: 20,"),
+        "got: {}",
+        err
+    );
+}
@@ -0,0 +1,213 @@
+//! Per-instance state of validation in a [`Valid`](crate::Valid): the settings and the failure
+//! policy.
+//!
+//! With feature `strip`, when validation is compiled out, it is a zero-sized type that keeps
+//! nothing, so that `Valid<T>` is `#[repr(transparent)]` over `T`.
+
+#[cfg(not(validit_stripped))]
+pub(crate) use checked::CheckState;
+#[cfg(validit_stripped)]
+pub(crate) use stripped::CheckState;
+
+#[cfg(not(validit_stripped))]
+mod checked {
+    use std::error::Error;
+    #[cfg(feature = "track-mutation")]
+    use std::panic::Location;
+
+    use crate::FailurePolicy;
+    #[cfg(feature = "dirty-tracking")]
+    use crate::clean;
+
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) struct CheckState<P> {
+        /// Per-instance setting of validation. `None` follows the process-wide setting.
+        enabled: Option<bool>,
+
+        /// Whether to skip validation if the value is not mutated since the last validation.
+        #[cfg(feature = "dirty-tracking")]
+        track_dirty: bool,
+
+        /// Replaced with a new one every time a mutable reference is handed out, see [`clean`].
+        #[cfg(feature = "dirty-tracking")]
+        generation: u64,
+
+        /// Where the value is mutated the last time.
+        #[cfg(feature = "track-mutation")]
+        last_mutation: Option<&'static Location<'static>>,
+
+        policy: P,
+    }
+
+    impl<P> CheckState<P> {
+        pub(crate) fn new(policy: P) -> Self {
+            Self {
+                enabled: None,
+                #[cfg(feature = "dirty-tracking")]
+                track_dirty: true,
+                #[cfg(feature = "dirty-tracking")]
+                generation: clean::next_generation(),
+                #[cfg(feature = "track-mutation")]
+                last_mutation: None,
+                policy,
+            }
+        }
+
+        /// Return a state with the same settings and policy for another value.
+        pub(crate) fn inherit(&self) -> Self
+        where P: Clone {
+            Self {
+                enabled: self.enabled,
+                #[cfg(feature = "dirty-tracking")]
+                track_dirty: self.track_dirty,
+                #[cfg(feature = "dirty-tracking")]
+                generation: clean::next_generation(),
+                #[cfg(feature = "track-mutation")]
+                last_mutation: None,
+                policy: self.policy.clone(),
+            }
+        }
+
+        pub(crate) fn enabled(&self) -> Option<bool> {
+            self.enabled
+        }
+
+        pub(crate) fn set_enabled(&mut self, enabled: bool) {
+            self.enabled = Some(enabled);
+        }
+
+        #[cfg(feature = "dirty-tracking")]
+        pub(crate) fn track_dirty(&self) -> bool {
+            self.track_dirty
+        }
+
+        #[cfg(feature = "dirty-tracking")]
+        pub(crate) fn set_track_dirty(&mut self, track_dirty: bool) {
+            self.track_dirty = track_dirty;
+        }
+
+        #[cfg(feature = "dirty-tracking")]
+        pub(crate) fn generation(&self) -> u64 {
+            self.generation
+        }
+
+        /// Mark the value as mutated, before handing out a mutable reference to it.
+        pub(crate) fn mark_dirty(&mut self) {
+            #[cfg(feature = "dirty-tracking")]
+            {
+                self.generation = clean::next_generation();
+            }
+        }
+
+        #[cfg(feature = "track-mutation")]
+        pub(crate) fn last_mutation(&self) -> Option<&'static Location<'static>> {
+            self.last_mutation
+        }
+
+        #[cfg(feature = "track-mutation")]
+        pub(crate) fn set_last_mutation(&mut self, location: &'static Location<'static>) {
+            self.last_mutation = Some(location);
+        }
+
+        /// Handle an invalid state of a `T` with the policy.
+        #[track_caller]
+        pub(crate) fn on_failure<T: ?Sized>(&self, err: &(dyn Error + 'static))
+        where P: FailurePolicy<T> {
+            self.policy.on_failure(err)
+        }
+
+        /// Return whether the policy validates this access to a `T`.
+        pub(crate) fn should_validate<T: ?Sized>(&self) -> bool
+        where P: FailurePolicy<T> {
+            self.policy.should_validate()
+        }
+
+        pub(crate) fn on_valid<T: ?Sized>(&self, inner: &T)
+        where P: FailurePolicy<T> {
+            self.policy.on_valid(inner)
+        }
+
+        pub(crate) fn context<T: ?Sized>(&self, inner: &T) -> Option<String>
+        where P: FailurePolicy<T> {
+            self.policy.context(inner)
+        }
+    }
+}
+
+#[cfg(validit_stripped)]
+mod stripped {
+    use std::error::Error;
+    use std::marker::PhantomData;
+    #[cfg(feature = "track-mutation")]
+    use std::panic::Location;
+
+    use crate::FailurePolicy;
+
+    /// Validation is compiled out, there is nothing to keep, and the policy is never used.
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) struct CheckState<P> {
+        policy: PhantomData<P>,
+    }
+
+    impl<P> CheckState<P> {
+        pub(crate) fn new(_policy: P) -> Self {
+            Self {
+                policy: PhantomData,
+            }
+        }
+
+        pub(crate) fn inherit(&self) -> Self
+        where P: Clone {
+            Self {
+                policy: PhantomData,
+            }
+        }
+
+        pub(crate) fn enabled(&self) -> Option<bool> {
+            Some(false)
+        }
+
+        pub(crate) fn set_enabled(&mut self, _enabled: bool) {}
+
+        #[cfg(feature = "dirty-tracking")]
+        pub(crate) fn track_dirty(&self) -> bool {
+            false
+        }
+
+        #[cfg(feature = "dirty-tracking")]
+        pub(crate) fn set_track_dirty(&mut self, _track_dirty: bool) {}
+
+        #[cfg(feature = "dirty-tracking")]
+        pub(crate) fn generation(&self) -> u64 {
+            0
+        }
+
+        pub(crate) fn mark_dirty(&mut self) {}
+
+        #[cfg(feature = "track-mutation")]
+        pub(crate) fn last_mutation(&self) -> Option<&'static Location<'static>> {
+            None
+        }
+
+        #[cfg(feature = "track-mutation")]
+        pub(crate) fn set_last_mutation(&mut self, _location: &'static Location<'static>) {}
+
+        pub(crate) fn on_failure<T: ?Sized>(&self, _err: &(dyn Error + 'static))
+        where P: FailurePolicy<T> {
+        }
+
+        pub(crate) fn should_validate<T: ?Sized>(&self) -> bool
+        where P: FailurePolicy<T> {
+            false
+        }
+
+        pub(crate) fn on_valid<T: ?Sized>(&self, _inner: &T)
+        where P: FailurePolicy<T> {
+        }
+
+        pub(crate) fn context<T: ?Sized>(&self, _inner: &T) -> Option<String>
+        where P: FailurePolicy<T> {
+            None
+        }
+    }
+}
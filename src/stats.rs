@@ -0,0 +1,196 @@
+//! Statistics of validation per type, enabled by feature `stats`.
+//!
+//! Every call to [`Validate::validate()`] made by this crate is recorded under the type name of
+//! the value: the number of validations, the number of failures, and the total and the max time
+//! spent. It tells how much a build with validation is slowed down by each type.
+//!
+//! ```
+//! # use std::error::Error;
+//! # use validit::Validate;
+//! # use validit::ValidateExt;
+//! struct Foo { a: u64 }
+//! impl Validate for Foo {
+//!     fn validate(&self) -> Result<(), Box<dyn Error>> {
+//!         validit::less_equal!(self.a, 10);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let f = Foo { a: 5 }.valid();
+//! let _x = f.a;
+//!
+//! let stats = validit::stats::snapshot();
+//! assert_eq!(1, stats[std::any::type_name::<Foo>()].validations);
+//!
+//! // Print the statistics to stderr when `_report` is dropped, such as at the end of `main()`.
+//! let _report = validit::stats::PrintOnDrop;
+//! ```
+//!
+//! Every thread records into its own table, thus validations in different threads do not contend.
+//! The table of a thread is merged into a process-wide one when the thread exits.
+//!
+//! With feature `metrics`, the statistics are also reported with the
+//! [`metrics`](https://docs.rs/metrics) crate, labeled with `type`:
+//! - counter `validit_validations_total`,
+//! - counter `validit_failures_total`,
+//! - histogram `validit_validation_seconds`.
+
+use std::any::type_name;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::Validate;
+
+/// Statistics of validation of one type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeStats {
+    /// The number of calls to [`Validate::validate()`].
+    pub validations: u64,
+
+    /// The number of validations that return an error.
+    pub failures: u64,
+
+    /// The total time spent in [`Validate::validate()`].
+    pub total_time: Duration,
+
+    /// The max time spent in a single call to [`Validate::validate()`].
+    pub max_time: Duration,
+}
+
+impl TypeStats {
+    fn merge(&mut self, other: &TypeStats) {
+        self.validations += other.validations;
+        self.failures += other.failures;
+        self.total_time += other.total_time;
+        self.max_time = self.max_time.max(other.max_time);
+    }
+}
+
+type Table = BTreeMap<&'static str, TypeStats>;
+
+/// The tables of the running threads.
+static THREADS: Mutex<Vec<Arc<Mutex<Table>>>> = Mutex::new(Vec::new());
+
+/// The statistics of the exited threads.
+static EXITED: Mutex<Table> = Mutex::new(BTreeMap::new());
+
+/// The table of the current thread, which is registered in [`THREADS`] when it is created, and
+/// merged into [`EXITED`] when the thread exits.
+struct LocalTable(Arc<Mutex<Table>>);
+
+impl LocalTable {
+    fn new() -> Self {
+        let table = Arc::new(Mutex::new(Table::new()));
+        lock(&THREADS).push(table.clone());
+        Self(table)
+    }
+}
+
+impl Drop for LocalTable {
+    fn drop(&mut self) {
+        // Lock `THREADS` first, the same order as `snapshot()`, so that a snapshot sees the table
+        // either in `THREADS` or merged into `EXITED`.
+        let mut threads = lock(&THREADS);
+        threads.retain(|t| !Arc::ptr_eq(t, &self.0));
+        merge(&mut lock(&EXITED), &lock(&self.0));
+    }
+}
+
+thread_local! {
+    static LOCAL: LocalTable = LocalTable::new();
+}
+
+fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn merge(to: &mut Table, from: &Table) {
+    for (name, s) in from {
+        to.entry(name).or_default().merge(s);
+    }
+}
+
+/// Return the statistics of all types that are validated, indexed by type name.
+pub fn snapshot() -> BTreeMap<&'static str, TypeStats> {
+    let threads = lock(&THREADS);
+    let mut stats = lock(&EXITED).clone();
+    for t in threads.iter() {
+        merge(&mut stats, &lock(t));
+    }
+    stats
+}
+
+/// Clear the statistics of all types.
+pub fn reset() {
+    let threads = lock(&THREADS);
+    lock(&EXITED).clear();
+    for t in threads.iter() {
+        lock(t).clear();
+    }
+}
+
+/// Render the statistics of all types, one line per type.
+pub fn report() -> String {
+    let mut s = String::from("validit stats:\n");
+    for (name, t) in snapshot() {
+        let _ = writeln!(
+            s,
+            "  {}: validations: {}, failures: {}, total: {:?}, max: {:?}",
+            name, t.validations, t.failures, t.total_time, t.max_time
+        );
+    }
+    s
+}
+
+/// Print the statistics to stderr with [`report()`] when dropped.
+///
+/// Keep it in `main()` to print the statistics when the program finishes:
+/// `let _report = validit::stats::PrintOnDrop;`.
+#[derive(Debug, Default)]
+pub struct PrintOnDrop;
+
+impl Drop for PrintOnDrop {
+    fn drop(&mut self) {
+        eprint!("{}", report());
+    }
+}
+
+/// Call [`Validate::validate()`] and record the time spent and the result.
+pub(crate) fn measure<T>(inner: &T) -> Result<(), Box<dyn Error>>
+where T: Validate + ?Sized {
+    let start = Instant::now();
+    let res = inner.validate();
+    let elapsed = start.elapsed();
+
+    let name = type_name::<T>();
+
+    let stats = TypeStats {
+        validations: 1,
+        failures: res.is_err() as u64,
+        total_time: elapsed,
+        max_time: elapsed,
+    };
+
+    // A validation made while the thread is exiting, after its table is dropped, is not recorded.
+    let _ = LOCAL.try_with(|local| {
+        lock(&local.0).entry(name).or_default().merge(&stats);
+    });
+
+    #[cfg(feature = "metrics")]
+    {
+        metrics::counter!("validit_validations_total", "type" => name).increment(1);
+        if res.is_err() {
+            metrics::counter!("validit_failures_total", "type" => name).increment(1);
+        }
+        metrics::histogram!("validit_validation_seconds", "type" => name)
+            .record(elapsed.as_secs_f64());
+    }
+
+    res
+}
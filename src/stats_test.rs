@@ -0,0 +1,81 @@
+use std::any::type_name;
+
+use crate::ValidateExt;
+use crate::stats;
+use crate::testing::Counted;
+
+#[test]
+fn test_stats_snapshot() {
+    let get = || stats::snapshot().get(type_name::<Counted<10>>()).copied();
+
+    assert_eq!(None, get());
+
+    let mut f = Counted::<10> { a: 1 }.valid();
+    // Otherwise a clean value is validated only once.
+    #[cfg(feature = "dirty-tracking")]
+    f.enable_dirty_tracking(false);
+
+    let _x = f.a;
+    let _x = f.a;
+    f.a = 20;
+    assert!(f.try_get().is_err());
+
+    let s = get().unwrap();
+    assert_eq!(4, s.validations);
+    assert_eq!(1, s.failures);
+    assert!(s.max_time <= s.total_time);
+}
+
+#[test]
+fn test_stats_explicit_validation() {
+    let get = || stats::snapshot().get(type_name::<Counted<11>>()).copied();
+
+    assert!(Counted::<11> { a: 1 }.try_valid().is_ok());
+    assert!(Counted::<11> { a: 20 }.try_valid().is_err());
+
+    let s = get().unwrap();
+    assert_eq!(2, s.validations);
+    assert_eq!(1, s.failures);
+}
+
+#[test]
+fn test_stats_other_threads() {
+    let get = || stats::snapshot().get(type_name::<Counted<12>>()).copied();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let running = std::thread::spawn(move || {
+        assert!(Counted::<12> { a: 1 }.try_valid().is_ok());
+        tx.send(()).unwrap();
+        std::thread::park();
+    });
+    rx.recv().unwrap();
+
+    // A running thread
+    assert_eq!(1, get().unwrap().validations);
+
+    running.thread().unpark();
+    running.join().unwrap();
+
+    // An exited thread
+    std::thread::spawn(|| Counted::<12> { a: 20 }.try_valid().is_err()).join().unwrap();
+
+    let s = get().unwrap();
+    assert_eq!(2, s.validations);
+    assert_eq!(1, s.failures);
+}
+
+#[test]
+fn test_stats_report() {
+    assert!(Counted::<13> { a: 1 }.try_valid().is_ok());
+
+    let report = stats::report();
+    assert!(report.starts_with("validit stats:\n"), "got: {}", report);
+    assert!(
+        report.contains(&format!(
+            "  {}: validations: 1, failures: 0, total: ",
+            type_name::<Counted<13>>()
+        )),
+        "got: {}",
+        report
+    );
+}
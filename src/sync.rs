@@ -0,0 +1,323 @@
+//! Locks that validate the protected value when it is locked and when a write guard is released.
+//!
+//! With `Arc<Mutex<Valid<T>>>`, an invalid state written in a critical section is found by the
+//! next access, which may be in another thread. [`ValidMutex`] and [`ValidRwLock`] validate the
+//! value when a write guard is dropped, so that a violation is attributed to the critical section
+//! that causes it, along with the location where the lock is acquired.
+//!
+//! - A read guard validates the value when it is acquired.
+//! - A write guard validates the value when it is acquired and when it is dropped.
+//!
+//! The error passed to the policy is an [`AccessError`] located where the lock is acquired, and
+//! marked by [`AccessKind::Lock`] or [`AccessKind::AfterWrite`].
+//!
+//! Like [`Valid`](crate::Valid), validation runs only when it is compiled in, and follows the
+//! process-wide setting and the sampling of the policy, such as
+//! [`Sampled`](crate::policy::Sampled).
+//!
+//! With feature `parking_lot`, the same locks based on `parking_lot` are provided in
+//! [`sync::parking_lot`](crate::sync::parking_lot).
+//!
+//! ```
+//! # use std::error::Error;
+//! # use std::panic::catch_unwind;
+//! # use validit::Validate;
+//! # use validit::sync::ValidMutex;
+//! struct Foo { a: u64 }
+//! impl Validate for Foo {
+//!     fn validate(&self) -> Result<(), Box<dyn Error>> {
+//!         validit::less_equal!(self.a, 10);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let m = ValidMutex::new(Foo { a: 5 });
+//! m.lock().unwrap().a = 6;
+//!
+//! let res = catch_unwind(|| {
+//!     m.lock().unwrap().a = 20; // panic: ... detected in `Foo` after write at src/main.rs:...
+//! });
+//! assert!(res.is_err());
+//! ```
+
+#[cfg(feature = "parking_lot")]
+pub mod parking_lot;
+
+use std::any::type_name;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::panic::Location;
+use std::sync::LockResult;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
+
+use crate::AccessError;
+use crate::AccessKind;
+use crate::FailurePolicy;
+use crate::Validate;
+use crate::global;
+use crate::policy::Panic;
+use crate::validate;
+
+/// A guard returned by [`ValidMutex::lock()`].
+pub type ValidMutexGuard<'a, T, P = Panic> = ValidWriteGuard<'a, MutexGuard<'a, T>, P>;
+
+/// A guard returned by [`ValidRwLock::write()`].
+pub type ValidRwLockWriteGuard<'a, T, P = Panic> = ValidWriteGuard<'a, RwLockWriteGuard<'a, T>, P>;
+
+/// A mutex that validates the protected value, see [module doc](crate::sync).
+#[derive(Debug, Default)]
+pub struct ValidMutex<T, P = Panic>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    policy: P,
+    mutex: Mutex<T>,
+}
+
+impl<T> ValidMutex<T>
+where T: Validate
+{
+    /// Create a new `ValidMutex<T>` that panics when an invalid state is found.
+    pub fn new(inner: T) -> Self {
+        Self::with_policy(inner, Panic)
+    }
+}
+
+impl<T, P> ValidMutex<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    /// Create a new `ValidMutex<T, P>` that handles an invalid state with `policy`.
+    pub fn with_policy(inner: T, policy: P) -> Self
+    where T: Sized {
+        Self {
+            policy,
+            mutex: Mutex::new(inner),
+        }
+    }
+
+    /// Acquire the lock and validate the value.
+    ///
+    /// The returned guard validates the value again when it is dropped. The guard of a poisoned
+    /// lock is returned in a [`PoisonError`] as [`Mutex::lock()`] does, and is not validated.
+    #[track_caller]
+    pub fn lock(&self) -> LockResult<ValidMutexGuard<'_, T, P>> {
+        let location = Location::caller();
+        wrap_write(self.mutex.lock(), &self.policy, location)
+    }
+
+    /// Consume self and return the protected value without validating it.
+    pub fn into_inner(self) -> LockResult<T>
+    where T: Sized {
+        self.mutex.into_inner()
+    }
+}
+
+/// A reader-writer lock that validates the protected value, see [module doc](crate::sync).
+#[derive(Debug, Default)]
+pub struct ValidRwLock<T, P = Panic>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    policy: P,
+    lock: RwLock<T>,
+}
+
+impl<T> ValidRwLock<T>
+where T: Validate
+{
+    /// Create a new `ValidRwLock<T>` that panics when an invalid state is found.
+    pub fn new(inner: T) -> Self {
+        Self::with_policy(inner, Panic)
+    }
+}
+
+impl<T, P> ValidRwLock<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    /// Create a new `ValidRwLock<T, P>` that handles an invalid state with `policy`.
+    pub fn with_policy(inner: T, policy: P) -> Self
+    where T: Sized {
+        Self {
+            policy,
+            lock: RwLock::new(inner),
+        }
+    }
+
+    /// Acquire a read lock and validate the value.
+    ///
+    /// A poisoned lock is returned as it is without validation.
+    #[track_caller]
+    pub fn read(&self) -> LockResult<RwLockReadGuard<'_, T>> {
+        let guard = self.lock.read()?;
+        if let Err(e) = check(&*guard, &self.policy, AccessKind::Lock, Location::caller()) {
+            self.policy.on_failure(&e);
+        }
+        Ok(guard)
+    }
+
+    /// Acquire a write lock and validate the value.
+    ///
+    /// The returned guard validates the value again when it is dropped.
+    #[track_caller]
+    pub fn write(&self) -> LockResult<ValidRwLockWriteGuard<'_, T, P>> {
+        let location = Location::caller();
+        wrap_write(self.lock.write(), &self.policy, location)
+    }
+
+    /// Consume self and return the protected value without validating it.
+    pub fn into_inner(self) -> LockResult<T>
+    where T: Sized {
+        self.lock.into_inner()
+    }
+}
+
+/// A guard for writing to the value protected by a lock, which validates the value when it is
+/// dropped.
+///
+/// `G` is the guard of the underlying lock, such as [`MutexGuard`].
+pub struct ValidWriteGuard<'a, G, P = Panic>
+where
+    G: DerefMut,
+    G::Target: Validate,
+    P: FailurePolicy<G::Target>,
+{
+    guard: G,
+    policy: &'a P,
+
+    /// Where the lock is acquired, to locate the critical section that breaks the state.
+    location: &'static Location<'static>,
+
+    /// A guard of a poisoned lock is not validated.
+    poisoned: bool,
+}
+
+impl<'a, G, P> ValidWriteGuard<'a, G, P>
+where
+    G: DerefMut,
+    G::Target: Validate,
+    P: FailurePolicy<G::Target>,
+{
+    /// Validate the value behind a newly acquired `guard` and wrap it.
+    #[track_caller]
+    pub(crate) fn new(guard: G, policy: &'a P, location: &'static Location<'static>) -> Self {
+        if let Err(e) = check(&*guard, policy, AccessKind::Lock, location) {
+            policy.on_failure(&e);
+        }
+
+        Self {
+            guard,
+            policy,
+            location,
+            poisoned: false,
+        }
+    }
+}
+
+impl<G, P> Deref for ValidWriteGuard<'_, G, P>
+where
+    G: DerefMut,
+    G::Target: Validate,
+    P: FailurePolicy<G::Target>,
+{
+    type Target = G::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<G, P> DerefMut for ValidWriteGuard<'_, G, P>
+where
+    G: DerefMut,
+    G::Target: Validate,
+    P: FailurePolicy<G::Target>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<G, P> Drop for ValidWriteGuard<'_, G, P>
+where
+    G: DerefMut,
+    G::Target: Validate,
+    P: FailurePolicy<G::Target>,
+{
+    fn drop(&mut self) {
+        // Do not panic again if it is already panicking, which aborts the process.
+        if !std::thread::panicking()
+            && !self.poisoned
+            && let Err(e) = check(
+                &*self.guard,
+                self.policy,
+                AccessKind::AfterWrite,
+                self.location,
+            )
+        {
+            self.policy.on_failure(&e);
+        }
+    }
+}
+
+/// Wrap a guard of a std lock. A poisoned guard is not validated, neither now nor when dropped.
+#[track_caller]
+fn wrap_write<'a, G, P>(
+    res: LockResult<G>,
+    policy: &'a P,
+    location: &'static Location<'static>,
+) -> LockResult<ValidWriteGuard<'a, G, P>>
+where
+    G: DerefMut,
+    G::Target: Validate,
+    P: FailurePolicy<G::Target>,
+{
+    match res {
+        Ok(guard) => Ok(ValidWriteGuard::new(guard, policy, location)),
+        Err(poisoned) => Err(PoisonError::new(ValidWriteGuard {
+            guard: poisoned.into_inner(),
+            policy,
+            location,
+            poisoned: true,
+        })),
+    }
+}
+
+/// Validate a value protected by a lock, if validation is enabled for it.
+///
+/// The error is detected at `location` in the way of `kind`.
+pub(crate) fn check<T, P>(
+    inner: &T,
+    policy: &P,
+    kind: AccessKind,
+    location: &'static Location<'static>,
+) -> Result<(), AccessError>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    if !crate::CHECKS_COMPILED || !global::is_enabled::<T>() {
+        return Ok(());
+    }
+
+    if policy.should_validate() {
+        if let Err(e) = validate::run(inner) {
+            let err = AccessError::new(e, type_name::<T>(), location)
+                .with_kind(kind)
+                .with_context(policy.context(inner));
+            return Err(err);
+        }
+        policy.on_valid(inner);
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//! [`ValidMutex`] and [`ValidRwLock`] based on `parking_lot`, enabled by feature `parking_lot`.
+//!
+//! They behave the same as the ones in [`sync`](crate::sync), except that the locks are never
+//! poisoned.
+
+use std::panic::Location;
+
+use parking_lot::Mutex;
+use parking_lot::MutexGuard;
+use parking_lot::RwLock;
+use parking_lot::RwLockReadGuard;
+use parking_lot::RwLockWriteGuard;
+
+use crate::AccessKind;
+use crate::FailurePolicy;
+use crate::Validate;
+use crate::policy::Panic;
+use crate::sync::ValidWriteGuard;
+use crate::sync::check;
+
+/// A guard returned by [`ValidMutex::lock()`].
+pub type ValidMutexGuard<'a, T, P = Panic> = ValidWriteGuard<'a, MutexGuard<'a, T>, P>;
+
+/// A guard returned by [`ValidRwLock::write()`].
+pub type ValidRwLockWriteGuard<'a, T, P = Panic> = ValidWriteGuard<'a, RwLockWriteGuard<'a, T>, P>;
+
+/// A `parking_lot` mutex that validates the protected value, see [`sync`](crate::sync).
+#[derive(Debug, Default)]
+pub struct ValidMutex<T, P = Panic>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    policy: P,
+    mutex: Mutex<T>,
+}
+
+impl<T> ValidMutex<T>
+where T: Validate
+{
+    /// Create a new `ValidMutex<T>` that panics when an invalid state is found.
+    pub fn new(inner: T) -> Self {
+        Self::with_policy(inner, Panic)
+    }
+}
+
+impl<T, P> ValidMutex<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    /// Create a new `ValidMutex<T, P>` that handles an invalid state with `policy`.
+    pub fn with_policy(inner: T, policy: P) -> Self
+    where T: Sized {
+        Self {
+            policy,
+            mutex: Mutex::new(inner),
+        }
+    }
+
+    /// Acquire the lock and validate the value.
+    ///
+    /// The returned guard validates the value again when it is dropped.
+    #[track_caller]
+    pub fn lock(&self) -> ValidMutexGuard<'_, T, P> {
+        let location = Location::caller();
+        ValidWriteGuard::new(self.mutex.lock(), &self.policy, location)
+    }
+
+    /// Consume self and return the protected value without validating it.
+    pub fn into_inner(self) -> T
+    where T: Sized {
+        self.mutex.into_inner()
+    }
+}
+
+/// A `parking_lot` reader-writer lock that validates the protected value, see
+/// [`sync`](crate::sync).
+#[derive(Debug, Default)]
+pub struct ValidRwLock<T, P = Panic>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    policy: P,
+    lock: RwLock<T>,
+}
+
+impl<T> ValidRwLock<T>
+where T: Validate
+{
+    /// Create a new `ValidRwLock<T>` that panics when an invalid state is found.
+    pub fn new(inner: T) -> Self {
+        Self::with_policy(inner, Panic)
+    }
+}
+
+impl<T, P> ValidRwLock<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    /// Create a new `ValidRwLock<T, P>` that handles an invalid state with `policy`.
+    pub fn with_policy(inner: T, policy: P) -> Self
+    where T: Sized {
+        Self {
+            policy,
+            lock: RwLock::new(inner),
+        }
+    }
+
+    /// Acquire a read lock and validate the value.
+    #[track_caller]
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        let guard = self.lock.read();
+        if let Err(e) = check(&*guard, &self.policy, AccessKind::Lock, Location::caller()) {
+            self.policy.on_failure(&e);
+        }
+        guard
+    }
+
+    /// Acquire a write lock and validate the value.
+    ///
+    /// The returned guard validates the value again when it is dropped.
+    #[track_caller]
+    pub fn write(&self) -> ValidRwLockWriteGuard<'_, T, P> {
+        let location = Location::caller();
+        ValidWriteGuard::new(self.lock.write(), &self.policy, location)
+    }
+
+    /// Consume self and return the protected value without validating it.
+    pub fn into_inner(self) -> T
+    where T: Sized {
+        self.lock.into_inner()
+    }
+}
@@ -0,0 +1,190 @@
+use std::any::type_name;
+use std::error::Error;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::AccessError;
+use crate::ValidationError;
+use crate::policy::Callback;
+use crate::sync::ValidMutex;
+use crate::sync::ValidRwLock;
+use crate::testing::Foo;
+
+fn panic_message(res: std::thread::Result<()>) -> String {
+    let err = res.unwrap_err();
+    err.downcast_ref::<String>().unwrap().clone()
+}
+
+#[test]
+fn test_valid_mutex() {
+    let m = ValidMutex::new(Foo { le_10: 1 });
+    {
+        let mut g = m.lock().unwrap();
+        g.le_10 += 5;
+        assert_eq!(6, g.le_10);
+    }
+    assert_eq!(6, m.into_inner().unwrap().le_10);
+}
+
+#[test]
+fn test_valid_mutex_panic_on_release() {
+    let m = Arc::new(ValidMutex::new(Foo { le_10: 1 }));
+
+    let m2 = m.clone();
+    let res = std::thread::spawn(move || {
+        m2.lock().unwrap().le_10 = 20;
+    })
+    .join();
+    let line = line!() - 3;
+
+    let msg = panic_message(res);
+    assert!(
+        msg.starts_with("invalid state: expect: self.le_10(20) <= 10(10) at "),
+        "got: {}",
+        msg
+    );
+    assert!(
+        msg.contains(&format!(
+            "\ndetected in `{}` after write at {}:{}:",
+            type_name::<Foo>(),
+            file!(),
+            line
+        )),
+        "got: {}",
+        msg
+    );
+
+    // The critical section that breaks the state poisons the lock.
+    assert!(m.lock().is_err());
+}
+
+#[test]
+fn test_valid_mutex_panic_on_acquire() {
+    let res = std::panic::catch_unwind(|| {
+        let m = ValidMutex::new(Foo { le_10: 20 });
+        let _g = m.lock();
+    });
+    let line = line!() - 2;
+
+    let msg = panic_message(res);
+    assert!(msg.starts_with("invalid state: expect: self.le_10(20) <= 10(10) at "));
+    assert!(
+        msg.contains(&format!(
+            "\ndetected in `{}` when locked at {}:{}:",
+            type_name::<Foo>(),
+            file!(),
+            line
+        )),
+        "got: {}",
+        msg
+    );
+}
+
+#[test]
+fn test_valid_rw_lock() {
+    let l = ValidRwLock::new(Foo { le_10: 1 });
+    l.write().unwrap().le_10 = 5;
+    assert_eq!(5, l.read().unwrap().le_10);
+
+    // Write guard validates on release
+    let res = std::panic::catch_unwind(|| {
+        let l = ValidRwLock::new(Foo { le_10: 1 });
+        l.write().unwrap().le_10 = 20;
+    });
+    let msg = panic_message(res);
+    assert!(
+        msg.contains(&format!(
+            "\ndetected in `{}` after write at ",
+            type_name::<Foo>()
+        )),
+        "got: {}",
+        msg
+    );
+
+    // Read guard validates on acquisition
+    let res = std::panic::catch_unwind(|| {
+        let l = ValidRwLock::new(Foo { le_10: 20 });
+        let _g = l.read();
+    });
+    let line = line!() - 2;
+
+    let msg = panic_message(res);
+    assert!(msg.starts_with("invalid state: expect: self.le_10(20) <= 10(10) at "));
+    assert!(
+        msg.contains(&format!(" when locked at {}:{}:", file!(), line)),
+        "got: {}",
+        msg
+    );
+}
+
+#[test]
+fn test_valid_lock_policy() {
+    static ERRORS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    fn on_failure(err: &(dyn Error + 'static)) {
+        ERRORS.lock().unwrap().push(err.to_string());
+
+        let access = err.downcast_ref::<AccessError>().unwrap();
+        assert_eq!(type_name::<Foo>(), access.type_name());
+        let source = access.source().unwrap();
+        assert!(source.downcast_ref::<ValidationError>().is_some());
+    }
+
+    let l = ValidRwLock::with_policy(Foo { le_10: 1 }, Callback(on_failure));
+    l.write().unwrap().le_10 = 20;
+    let line = line!() - 1;
+    {
+        let errors = ERRORS.lock().unwrap();
+        assert_eq!(1, errors.len());
+        assert!(
+            errors[0].contains(&format!(" after write at {}:{}:", file!(), line)),
+            "got: {}",
+            errors[0]
+        );
+    }
+
+    let _g = l.read().unwrap();
+    assert_eq!(2, ERRORS.lock().unwrap().len());
+}
+
+#[cfg(feature = "parking_lot")]
+#[test]
+fn test_parking_lot() {
+    use crate::sync::parking_lot;
+
+    let m = parking_lot::ValidMutex::new(Foo { le_10: 1 });
+    m.lock().le_10 = 5;
+    assert_eq!(5, m.into_inner().le_10);
+
+    let l = parking_lot::ValidRwLock::new(Foo { le_10: 1 });
+    l.write().le_10 = 5;
+    assert_eq!(5, l.read().le_10);
+
+    let res = std::panic::catch_unwind(|| {
+        let m = parking_lot::ValidMutex::new(Foo { le_10: 1 });
+        m.lock().le_10 = 20;
+    });
+    let msg = panic_message(res);
+    assert!(
+        msg.contains(&format!(
+            "\ndetected in `{}` after write at ",
+            type_name::<Foo>()
+        )),
+        "got: {}",
+        msg
+    );
+
+    let res = std::panic::catch_unwind(|| {
+        let l = parking_lot::ValidRwLock::new(Foo { le_10: 20 });
+        let _g = l.read();
+    });
+    let line = line!() - 2;
+
+    let msg = panic_message(res);
+    assert!(msg.starts_with("invalid state: expect: self.le_10(20) <= 10(10) at "));
+    assert!(
+        msg.contains(&format!(" when locked at {}:{}:", file!(), line)),
+        "got: {}",
+        msg
+    );
+}
@@ -0,0 +1,53 @@
+//! Fixtures shared by the tests.
+
+use std::error::Error;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use crate::Validate;
+use crate::less_equal;
+
+/// A value that is valid if `le_10 <= 10`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Foo {
+    pub(crate) le_10: u64,
+}
+
+impl Validate for Foo {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        less_equal!(self.le_10, 10);
+        Ok(())
+    }
+}
+
+/// Count the number of calls to `validate()` of the type `Counted<N>`.
+static VALIDATED: [AtomicU64; 32] = [const { AtomicU64::new(0) }; 32];
+
+/// A value that is valid if `a <= 10`, and counts the calls to its `validate()`.
+///
+/// Every test uses a distinct `N`, so that tests running in parallel do not share a counter, or
+/// the per-type statistics and events. Each test module takes a range of its own:
+/// - `0..10`: `sampling_test`;
+/// - `10..20`: `stats_test`;
+/// - `20..30`: `event_test`;
+/// - `30..`: `clean_test`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Counted<const N: usize> {
+    pub(crate) a: u64,
+}
+
+impl<const N: usize> Counted<N> {
+    /// The number of calls to `validate()` of `Counted<N>`.
+    pub(crate) fn validated() -> u64 {
+        VALIDATED[N].load(Ordering::Relaxed)
+    }
+}
+
+impl<const N: usize> Validate for Counted<N> {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        VALIDATED[N].fetch_add(1, Ordering::Relaxed);
+        less_equal!(self.a, 10);
+        Ok(())
+    }
+}
@@ -1,19 +1,39 @@
-use core::panic;
+use std::any::type_name;
+use std::borrow::Borrow;
+use std::borrow::BorrowMut;
 use std::cmp::Ordering;
+use std::error::Error;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::hash::Hash;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::panic::Location;
+use std::str::FromStr;
 
+use crate::AccessError;
+use crate::AccessKind;
+use crate::FailurePolicy;
 use crate::Validate;
+use crate::WriteGuard;
+#[cfg(feature = "dirty-tracking")]
+use crate::clean;
+use crate::policy::Panic;
+use crate::state::CheckState;
+use crate::validate;
 
 /// A wrapper of T that validate the state of T every time accessing it.
 ///
 /// - It validates the state before accessing it, i.e., if when a invalid state is written to it, it
 ///   won't panic until next time accessing it.
-/// - The validation is turned on only when `debug_assertions` is enabled.
+/// - The validation is turned on only when `debug_assertions` is enabled, or feature
+///   `release-checks` is enabled, see [`CHECKS_COMPILED`](crate::CHECKS_COMPILED).
+/// - The validation can be turned on or off for an instance with [`Valid::enable_validation()`], or
+///   process-wide with [`set_enabled()`](crate::set_enabled) and environment variable `VALIDIT`,
+///   see [`global`](crate::global).
 ///
 /// An example of defining field `a` whose value must not exceed `10`.
 /// ```ignore
@@ -37,112 +57,385 @@ use crate::Validate;
 /// - `PartialEq` or `Eq` is called.
 /// - `PartialOrd` or `Ord` is called.
 /// - `Hash` is called.
+/// - `Valid::as_ref()`, `AsRef`, `AsMut`, `Borrow`, `BorrowMut`, `Index` or `IndexMut` is called.
+/// - A reference is iterated with `IntoIterator`, or `Extend` is called.
+/// - An arithmetic or bitwise operator such as `+` or `+=` is applied.
+/// - A value is parsed with `FromStr`, which returns an error instead of calling the policy.
+///
+/// With feature `dirty-tracking`, a value that is not mutated since the last validation is not
+/// validated again, unless it is disabled by `Valid::enable_dirty_tracking()`.
+///
+/// For a type whose validation is expensive, policy [`Sampled`](crate::policy::Sampled) validates
+/// only a sample of the accesses.
+///
+/// The error of an access includes the name of `T` and the location of the caller that accesses
+/// it, and the default policy panics at that location.
+///
+/// To detect an invalid write at the statement that writes it, use [`Valid::write()`], which
+/// returns a guard that validates the state when it is dropped.
+///
+/// `AsRef<T>` is implemented for generic code bounded by `AsRef<T>`. The method call `v.as_ref()`
+/// resolves to the inherent [`Valid::as_ref()`], which returns a `Valid<&T, P>`; call
+/// `AsRef::<T>::as_ref(&v)` to get a `&T`.
+///
+/// `Valid<T>` does not implement `From<T>`: it would make the derived
+/// `TryFrom<T> for Valid<T>` conflict with the blanket `TryFrom<U> for T where U: Into<T>`, and a
+/// conversion that never fails is misleading for a type that may be invalid. Use [`Valid::new()`]
+/// to wrap a value, or [`Valid::try_new()`] to reject an invalid one.
 ///
 /// Validation is not triggered when:
 /// - `Copy`: Because it is just a byte copy.
 /// - `Debug` and `Display`: for being able to examine the value for debugging.
-pub struct Valid<T>
-where T: Validate
+///
+/// `T` can be unsized, such as `Box<Valid<[T]>>` or `Box<Valid<dyn Validate>>`, which is built by
+/// coercing from a sized `Valid`, e.g., `Box<Valid<Foo>>`. `Validate` is implemented for `[T]`,
+/// `[T; N]` and `Vec<T>` by validating every element, and for `Rc<T>` and `Arc<T>` by forwarding
+/// to `T`. With feature `box-impl`, it is also implemented for `Box<T>`.
+///
+/// What to do with an invalid state is defined by the policy `P`, see [`policy`](crate::policy).
+/// By default it panics.
+///
+/// With feature `strip`, when validation is compiled out, `Valid<T, P>` is `#[repr(transparent)]`
+/// and has the same layout as `T`.
+#[cfg_attr(validit_stripped, repr(transparent))]
+pub struct Valid<T, P = Panic>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
-    pub(crate) enabled: bool,
-    inner: T,
+    pub(crate) state: CheckState<P>,
+    pub(crate) inner: T,
 }
 
 impl<T: Validate> Valid<T> {
-    /// Create a new `Valid<T>`.
+    /// Create a new `Valid<T>` that panics when an invalid state is found.
     pub fn new(inner: T) -> Self {
+        Self::with_policy(inner, Panic)
+    }
+
+    /// Create a new `Valid<T>` if `inner` is valid, otherwise return the error from
+    /// [`Validate::validate()`].
+    ///
+    /// Unlike accessing a `Valid`, the state is always validated, regardless of build profile or
+    /// the validation setting, so that an invalid input is rejected where it enters.
+    pub fn try_new(inner: T) -> Result<Self, Box<dyn Error>> {
+        Self::try_with_policy(inner, Panic)
+    }
+}
+
+impl<T, P> Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    /// Create a new `Valid<T, P>` that handles an invalid state with `policy`.
+    pub fn with_policy(inner: T, policy: P) -> Self
+    where T: Sized {
         Self {
-            enabled: true,
+            state: CheckState::new(policy),
             inner,
         }
     }
 
+    /// Create a new `Valid<T, P>` if `inner` is valid, otherwise return the error from
+    /// [`Validate::validate()`]. See [`Valid::try_new()`].
+    pub fn try_with_policy(inner: T, policy: P) -> Result<Self, Box<dyn Error>>
+    where T: Sized {
+        validate::run(&inner)?;
+        Ok(Self::with_policy(inner, policy))
+    }
+
     /// Set whether to validate the state when accessing it.
+    ///
+    /// It overrides the process-wide setting, see [`global`](crate::global).
     pub fn enable_validation(&mut self, enabled: bool) {
-        self.enabled = enabled;
+        self.state.set_enabled(enabled);
     }
 
     /// Get whether to validate the state when accessing it.
+    ///
+    /// It returns the per-instance setting if it is set by [`Valid::enable_validation()`],
+    /// otherwise the process-wide setting for `T`.
     pub fn is_enabled(&self) -> bool {
-        self.enabled
+        match self.state.enabled() {
+            Some(enabled) => enabled,
+            None => crate::global::is_enabled::<T>(),
+        }
     }
 
-    /// Return a reference to the wrapped value: `Valid<&T>`.
-    pub fn as_ref(&self) -> Valid<&T> {
+    /// Set whether to skip validation when the value is not mutated since the last validation.
+    ///
+    /// It is provided by feature `dirty-tracking` and enabled by default with it: a value is marked
+    /// dirty when a mutable reference to it is handed out, e.g., by `DerefMut`, and reading a
+    /// clean value does not validate it again.
+    ///
+    /// Disable it for a type with interior mutability, whose state can be changed through a
+    /// shared reference.
+    #[cfg(feature = "dirty-tracking")]
+    pub fn enable_dirty_tracking(&mut self, enabled: bool) {
+        self.state.set_track_dirty(enabled);
+    }
+
+    /// Get whether to skip validation when the value is not mutated since the last validation.
+    #[cfg(feature = "dirty-tracking")]
+    pub fn is_dirty_tracking_enabled(&self) -> bool {
+        self.state.track_dirty()
+    }
+
+    /// Return a reference to the wrapped value: `Valid<&T, P>`.
+    #[track_caller]
+    pub fn as_ref<'a>(&'a self) -> Valid<&'a T, P>
+    where P: Clone + FailurePolicy<&'a T> {
         Valid {
-            enabled: self.enabled,
+            state: self.state.inherit(),
             inner: self.deref(),
         }
     }
 
+    /// Return a reference to the wrapped value if the state is valid, otherwise return an
+    /// [`AccessError`] whose [`source()`](Error::source) is the error from
+    /// [`Validate::validate()`].
+    ///
+    /// Unlike `Deref`, it does not panic. The state is validated only when validation is enabled
+    /// and compiled in, the same as `Deref`.
+    #[track_caller]
+    pub fn try_get(&self) -> Result<&T, AccessError> {
+        self.check_at_caller()?;
+        Ok(&self.inner)
+    }
+
+    /// Return a mutable reference to the wrapped value if the state is valid, otherwise return an
+    /// [`AccessError`].
+    ///
+    /// Unlike `DerefMut`, it does not panic. See [`Valid::try_get()`].
+    #[track_caller]
+    pub fn try_get_mut(&mut self) -> Result<&mut T, AccessError> {
+        self.check_at_caller()?;
+        self.mark_dirty();
+        Ok(&mut self.inner)
+    }
+
+    /// Return a guard for writing to the wrapped value, which validates the state when dropped.
+    ///
+    /// The state is validated before returning the guard, as `DerefMut` does. When the guard is
+    /// dropped the state is validated again, and if the state becomes invalid, the policy is
+    /// called with an error that includes the location where `write()` is called.
+    #[track_caller]
+    pub fn write(&mut self) -> WriteGuard<'_, T, P> {
+        self.deref_mut();
+        WriteGuard::new(self, Location::caller())
+    }
+
+    /// Update the wrapped value with `f` and validate it, restore the value if it becomes invalid.
+    ///
+    /// The value is cloned before calling `f`. If the updated value is invalid, the clone is
+    /// restored and the error from [`Validate::validate()`] is returned, instead of calling the
+    /// policy. If `f` panics, the clone is restored before the panic goes on. The updated value is
+    /// always validated, regardless of build profile or the validation setting.
+    ///
+    /// The value is not validated before calling `f`, thus it can be used to repair an invalid
+    /// value.
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # use validit::Valid;
+    /// # use validit::Validate;
+    /// #[derive(Clone)]
+    /// struct Range { start: u64, end: u64 }
+    /// impl Validate for Range {
+    ///     fn validate(&self) -> Result<(), Box<dyn Error>> {
+    ///         validit::less_equal!(self.start, self.end);
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut r = Valid::new(Range { start: 1, end: 5 });
+    /// let res = r.modify(|r| {
+    ///     r.start = 10;
+    ///     r.end = 8;
+    /// });
+    /// assert!(res.is_err());
+    /// assert_eq!((1, 5), (r.start, r.end));
+    /// ```
+    #[track_caller]
+    pub fn modify<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> Result<R, Box<dyn Error>>
+    where T: Clone {
+        self.mark_dirty();
+
+        let mut rollback = Rollback {
+            snapshot: Some(self.inner.clone()),
+            inner: &mut self.inner,
+        };
+
+        let ret = f(rollback.inner);
+        validate::run(&*rollback.inner)?;
+
+        rollback.snapshot = None;
+        drop(rollback);
+
+        self.state.on_valid(&self.inner);
+        Ok(ret)
+    }
+
     /// Consume self and return the wrapped value.
     ///
     /// This does NOT validate the state.
-    pub fn into_inner(self) -> T {
+    pub fn into_inner(self) -> T
+    where T: Sized {
         self.inner
     }
+
+    /// Validate the state if validation is enabled and compiled in, see
+    /// [`CHECKS_COMPILED`](crate::CHECKS_COMPILED).
+    ///
+    /// Only a sample of the calls validate if the policy says so. With feature `dirty-tracking`, a
+    /// value that is not mutated since the last successful validation is not validated
+    /// again.
+    pub(crate) fn check(&self) -> Result<(), Box<dyn Error>> {
+        if !crate::CHECKS_COMPILED || !self.is_enabled() {
+            return Ok(());
+        }
+
+        #[cfg(feature = "dirty-tracking")]
+        let addr = (&self.inner as *const T).cast::<()>() as usize;
+        #[cfg(feature = "dirty-tracking")]
+        let track_dirty = self.state.track_dirty();
+        #[cfg(feature = "dirty-tracking")]
+        if track_dirty && clean::is_clean(addr, self.state.generation()) {
+            return Ok(());
+        }
+
+        if self.state.should_validate() {
+            validate::run(&self.inner)?;
+            self.state.on_valid(&self.inner);
+            #[cfg(feature = "dirty-tracking")]
+            if track_dirty {
+                clean::set_clean(addr, self.state.generation());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the state as [`Valid::check()`] does, for an access at the caller.
+    ///
+    /// The error includes the context from the policy, the type name and where it is detected,
+    /// and with feature `track-mutation`, where the value is mutated the last time.
+    #[track_caller]
+    pub(crate) fn check_at_caller(&self) -> Result<(), AccessError> {
+        let Err(e) = self.check() else {
+            return Ok(());
+        };
+
+        Err(self.access_error(e, AccessKind::Access, Location::caller()))
+    }
+
+    /// Wrap an error from [`Valid::check()`] in an [`AccessError`] detected at `location`.
+    pub(crate) fn access_error(
+        &self,
+        err: Box<dyn Error>,
+        kind: AccessKind,
+        location: &'static Location<'static>,
+    ) -> AccessError {
+        let err = AccessError::new(err, type_name::<T>(), location)
+            .with_kind(kind)
+            .with_context(self.state.context(&self.inner));
+
+        #[cfg(feature = "track-mutation")]
+        let err = err.with_last_mutation(self.state.last_mutation());
+
+        err
+    }
+
+    /// Mark the value as mutated, before handing out a mutable reference to it.
+    ///
+    /// With feature `track-mutation`, the caller is recorded as the location of the mutation.
+    #[track_caller]
+    pub(crate) fn mark_dirty(&mut self) {
+        self.state.mark_dirty();
+
+        #[cfg(feature = "track-mutation")]
+        self.state.set_last_mutation(Location::caller());
+    }
 }
 
-impl<T> Deref for Valid<T>
-where T: Validate
+impl<T, P> Deref for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
     type Target = T;
 
+    #[track_caller]
     fn deref(&self) -> &Self::Target {
-        #[cfg(debug_assertions)]
-        if self.enabled
-            && let Err(e) = self.inner.validate()
-        {
-            panic!("invalid state: {}", e);
+        if let Err(e) = self.check_at_caller() {
+            self.state.on_failure(&e);
         }
 
         &self.inner
     }
 }
 
-impl<T> DerefMut for Valid<T>
-where T: Validate
+impl<T, P> DerefMut for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
+    #[track_caller]
     fn deref_mut(&mut self) -> &mut Self::Target {
-        #[cfg(debug_assertions)]
-        if self.enabled
-            && let Err(e) = self.inner.validate()
-        {
-            panic!("invalid state: {}", e);
+        if let Err(e) = self.check_at_caller() {
+            self.state.on_failure(&e);
         }
 
+        self.mark_dirty();
         &mut self.inner
     }
 }
 
-impl<T: PartialEq> PartialEq for Valid<T>
-where T: Validate
+impl<T: PartialEq, P> PartialEq for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
+    #[track_caller]
     fn eq(&self, other: &Self) -> bool {
         PartialEq::eq(self.deref(), other.deref())
     }
 }
 
-impl<T: Eq> Eq for Valid<T> where T: Validate {}
+impl<T: Eq, P> Eq for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+}
 
-impl<T: PartialOrd> PartialOrd for Valid<T>
-where T: Validate
+impl<T: PartialOrd, P> PartialOrd for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
+    #[track_caller]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         PartialOrd::partial_cmp(self.deref(), other.deref())
     }
 }
 
-impl<T: Ord> Ord for Valid<T>
-where T: Validate
+impl<T: Ord, P> Ord for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
+    #[track_caller]
     fn cmp(&self, other: &Self) -> Ordering {
         Ord::cmp(self.deref(), other.deref())
     }
 }
 
-impl<T: Debug> Debug for Valid<T>
-where T: Validate
+impl<T: Debug, P> Debug for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         // Do not use `deref()`, skip validation so that the state can be displayed.
@@ -150,8 +443,10 @@ where T: Validate
     }
 }
 
-impl<T: Display> Display for Valid<T>
-where T: Validate
+impl<T: Display, P> Display for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         // Do not use `deref()`, skip validation so that the state can be displayed.
@@ -159,34 +454,184 @@ where T: Validate
     }
 }
 
-impl<T: Clone> Clone for Valid<T>
-where T: Validate
+impl<T: Clone, P: Clone> Clone for Valid<T, P>
+where
+    T: Validate,
+    P: FailurePolicy<T>,
 {
+    #[track_caller]
     fn clone(&self) -> Self {
         Self {
-            enabled: self.enabled,
+            state: self.state.inherit(),
             inner: self.deref().clone(),
         }
     }
 }
 
-impl<T: Copy> Copy for Valid<T> where T: Validate {}
+impl<T: Copy, P: Copy> Copy for Valid<T, P>
+where
+    T: Validate,
+    P: FailurePolicy<T>,
+{
+}
 
-impl<T: Default> Default for Valid<T>
-where T: Validate
+impl<T: Default, P: Default> Default for Valid<T, P>
+where
+    T: Validate,
+    P: FailurePolicy<T>,
 {
     fn default() -> Self {
-        Self {
-            enabled: true,
-            inner: T::default(),
-        }
+        Self::with_policy(T::default(), P::default())
     }
 }
 
-impl<T: Hash> Hash for Valid<T>
-where T: Validate
+impl<T: Hash, P> Hash for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
 {
+    #[track_caller]
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.deref().hash(state)
     }
 }
+
+impl<T, P> AsRef<T> for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    #[track_caller]
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T, P> AsMut<T> for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    #[track_caller]
+    fn as_mut(&mut self) -> &mut T {
+        self.deref_mut()
+    }
+}
+
+impl<T, P> Borrow<T> for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    #[track_caller]
+    fn borrow(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T, P> BorrowMut<T> for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    #[track_caller]
+    fn borrow_mut(&mut self) -> &mut T {
+        self.deref_mut()
+    }
+}
+
+impl<T: Index<I>, I, P> Index<I> for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    type Output = T::Output;
+
+    #[track_caller]
+    fn index(&self, index: I) -> &Self::Output {
+        self.deref().index(index)
+    }
+}
+
+impl<T: IndexMut<I>, I, P> IndexMut<I> for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    #[track_caller]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        self.deref_mut().index_mut(index)
+    }
+}
+
+impl<'a, T, P> IntoIterator for &'a Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+    &'a T: IntoIterator,
+{
+    type Item = <&'a T as IntoIterator>::Item;
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    #[track_caller]
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref().into_iter()
+    }
+}
+
+impl<'a, T, P> IntoIterator for &'a mut Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+    &'a mut T: IntoIterator,
+{
+    type Item = <&'a mut T as IntoIterator>::Item;
+    type IntoIter = <&'a mut T as IntoIterator>::IntoIter;
+
+    #[track_caller]
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref_mut().into_iter()
+    }
+}
+
+impl<T: Extend<A>, A, P> Extend<A> for Valid<T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    #[track_caller]
+    fn extend<I: IntoIterator<Item = A>>(&mut self, iter: I) {
+        self.deref_mut().extend(iter)
+    }
+}
+
+/// Parse a `T` and validate it, see [`Valid::try_new()`].
+impl<T: FromStr, P: Default> FromStr for Valid<T, P>
+where
+    T: Validate,
+    T::Err: Into<Box<dyn Error>>,
+    P: FailurePolicy<T>,
+{
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inner = s.parse::<T>().map_err(Into::into)?;
+        Self::try_with_policy(inner, P::default())
+    }
+}
+
+/// Restore a value from the snapshot when dropped, unless the snapshot is taken away.
+///
+/// It restores the value of [`Valid::modify()`] either if the update is invalid or if it panics.
+struct Rollback<'a, T> {
+    inner: &'a mut T,
+    snapshot: Option<T>,
+}
+
+impl<T> Drop for Rollback<'_, T> {
+    fn drop(&mut self) {
+        if let Some(snapshot) = self.snapshot.take() {
+            *self.inner = snapshot;
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Arithmetic and bitwise operators for `Valid<T>`, forwarded to `T`.
+//!
+//! The operand `Valid<T>` is validated before applying the operator. The result of a binary
+//! operator is a `Valid<T>` with the same settings, which is validated when it is accessed.
+
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+use crate::FailurePolicy;
+use crate::Valid;
+use crate::Validate;
+
+impl<T, P> Valid<T, P>
+where
+    T: Validate,
+    P: FailurePolicy<T>,
+{
+    /// Validate the state and replace the wrapped value with `f(inner)`.
+    #[track_caller]
+    fn map_inner(self, f: impl FnOnce(T) -> T) -> Self {
+        let _ = self.deref();
+
+        let Valid { state, inner } = self;
+        let mut mapped = Valid {
+            state,
+            inner: f(inner),
+        };
+        mapped.mark_dirty();
+        mapped
+    }
+}
+
+macro_rules! forward_binary_op {
+    ($op: ident, $method: ident, $op_assign: ident, $method_assign: ident) => {
+        impl<T, P, Rhs> std::ops::$op<Rhs> for Valid<T, P>
+        where
+            T: Validate + std::ops::$op<Rhs, Output = T>,
+            P: FailurePolicy<T>,
+        {
+            type Output = Self;
+
+            #[track_caller]
+            fn $method(self, rhs: Rhs) -> Self::Output {
+                self.map_inner(|inner| std::ops::$op::$method(inner, rhs))
+            }
+        }
+
+        impl<T, P, Rhs> std::ops::$op_assign<Rhs> for Valid<T, P>
+        where
+            T: Validate + std::ops::$op_assign<Rhs> + ?Sized,
+            P: FailurePolicy<T>,
+        {
+            #[track_caller]
+            fn $method_assign(&mut self, rhs: Rhs) {
+                std::ops::$op_assign::$method_assign(self.deref_mut(), rhs)
+            }
+        }
+    };
+}
+
+macro_rules! forward_unary_op {
+    ($op: ident, $method: ident) => {
+        impl<T, P> std::ops::$op for Valid<T, P>
+        where
+            T: Validate + std::ops::$op<Output = T>,
+            P: FailurePolicy<T>,
+        {
+            type Output = Self;
+
+            #[track_caller]
+            fn $method(self) -> Self::Output {
+                self.map_inner(std::ops::$op::$method)
+            }
+        }
+    };
+}
+
+forward_binary_op!(Add, add, AddAssign, add_assign);
+forward_binary_op!(Sub, sub, SubAssign, sub_assign);
+forward_binary_op!(Mul, mul, MulAssign, mul_assign);
+forward_binary_op!(Div, div, DivAssign, div_assign);
+forward_binary_op!(Rem, rem, RemAssign, rem_assign);
+forward_binary_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+forward_binary_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+forward_binary_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+forward_binary_op!(Shl, shl, ShlAssign, shl_assign);
+forward_binary_op!(Shr, shr, ShrAssign, shr_assign);
+
+forward_unary_op!(Neg, neg);
+forward_unary_op!(Not, not);
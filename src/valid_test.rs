@@ -1,37 +1,135 @@
+use std::borrow::Borrow;
+use std::borrow::BorrowMut;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::num::ParseIntError;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::ops::Not;
+use std::panic::AssertUnwindSafe;
 use std::panic::UnwindSafe;
+use std::str::FromStr;
+use std::sync::Arc;
 
+use crate::OpKind;
 use crate::ValidateExt;
+use crate::ValidationError;
 use crate::less_equal;
+use crate::testing::Foo;
 use crate::valid::Valid;
 use crate::validate::Validate;
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
-struct Foo {
-    le_10: u64,
-}
-
 impl Foo {
     fn new_valid(le_10: u64) -> Valid<Self> {
         Self { le_10 }.valid()
     }
 }
 
-impl Validate for Foo {
+impl Display for Foo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Foo:{}", self.le_10)
+    }
+}
+
+impl Add<u64> for Foo {
+    type Output = Foo;
+
+    fn add(self, rhs: u64) -> Self::Output {
+        Foo {
+            le_10: self.le_10 + rhs,
+        }
+    }
+}
+
+impl AddAssign<u64> for Foo {
+    fn add_assign(&mut self, rhs: u64) {
+        self.le_10 += rhs;
+    }
+}
+
+impl Not for Foo {
+    type Output = Foo;
+
+    fn not(self) -> Self::Output {
+        Foo {
+            le_10: 10 - self.le_10,
+        }
+    }
+}
+
+impl FromStr for Foo {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Foo { le_10: s.parse()? })
+    }
+}
+
+/// A collection that contains at most 3 items.
+#[derive(Debug, Clone, Default)]
+struct Items {
+    items: Vec<u64>,
+}
+
+impl Items {
+    fn new_valid(n: u64) -> Valid<Self> {
+        Self {
+            items: (0..n).collect(),
+        }
+        .valid()
+    }
+}
+
+impl Validate for Items {
     fn validate(&self) -> Result<(), Box<dyn Error>> {
-        less_equal!(self.le_10, 10);
+        less_equal!(self.items.len(), 3);
         Ok(())
     }
 }
 
-impl Display for Foo {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Foo:{}", self.le_10)
+impl Index<usize> for Items {
+    type Output = u64;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.items[index]
+    }
+}
+
+impl IndexMut<usize> for Items {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.items[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a Items {
+    type Item = &'a u64;
+    type IntoIter = std::slice::Iter<'a, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Items {
+    type Item = &'a mut u64;
+    type IntoIter = std::slice::IterMut<'a, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter_mut()
+    }
+}
+
+impl Extend<u64> for Items {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, iter: I) {
+        self.items.extend(iter)
     }
 }
 
@@ -156,6 +254,104 @@ fn test_valid_trigger_validation() {
         let f = Foo::new_valid(20);
         let _r = f.as_ref();
     });
+
+    // AsRef, AsMut, Borrow, BorrowMut trigger panic
+    assert_panic(|| {
+        let _r: &Foo = AsRef::as_ref(&Foo::new_valid(20));
+    });
+    assert_panic(|| {
+        let _r: &mut Foo = AsMut::as_mut(&mut Foo::new_valid(20));
+    });
+    assert_panic(|| {
+        let _r: &Foo = Borrow::borrow(&Foo::new_valid(20));
+    });
+    assert_panic(|| {
+        let _r: &mut Foo = BorrowMut::borrow_mut(&mut Foo::new_valid(20));
+    });
+
+    // Index, IndexMut trigger panic
+    assert_panic(|| {
+        let _x = Items::new_valid(4)[0];
+    });
+    assert_panic(|| {
+        Items::new_valid(4)[0] = 1;
+    });
+
+    // IntoIterator for references trigger panic
+    assert_panic(|| for _x in &Items::new_valid(4) {});
+    assert_panic(|| for _x in &mut Items::new_valid(4) {});
+
+    // Extend trigger panic
+    assert_panic(|| {
+        Items::new_valid(4).extend([1]);
+    });
+
+    // Operators trigger panic
+    assert_panic(|| {
+        let _x = Foo::new_valid(20) + 1;
+    });
+    assert_panic(|| {
+        let mut f = Foo::new_valid(20);
+        f += 1;
+    });
+    assert_panic(|| {
+        let _x = !Foo::new_valid(20);
+    });
+
+    // FromStr returns an error
+    assert!("20".parse::<Valid<Foo>>().is_err());
+}
+
+#[test]
+fn test_valid_forward_traits() {
+    // Borrow: look up a set of `Valid<Foo>` with `Foo`
+    let mut set = HashSet::new();
+    set.insert(Foo::new_valid(1));
+    assert!(set.contains(&Foo { le_10: 1 }));
+
+    // Index, IndexMut, IntoIterator, Extend
+    let mut items = Items::new_valid(2);
+    items[1] = 5;
+    assert_eq!(5, items[1]);
+
+    for x in &mut items {
+        *x += 1;
+    }
+    assert_eq!(
+        vec![1, 6],
+        (&items).into_iter().copied().collect::<Vec<_>>()
+    );
+
+    items.extend([7]);
+    assert_eq!(vec![1, 6, 7], items.items);
+
+    // An invalid result is detected at the next access
+    items.extend([8]);
+    assert!(items.try_get().is_err());
+
+    // Operators
+    let f = Foo::new_valid(1) + 2;
+    assert_eq!(3, f.le_10);
+
+    let mut f = Foo::new_valid(1);
+    f += 2;
+    assert_eq!(3, f.le_10);
+
+    let f = !Foo::new_valid(1);
+    assert_eq!(9, f.le_10);
+
+    let f = Foo::new_valid(5) + 10;
+    assert!(f.try_get().is_err());
+
+    // FromStr
+    let f: Valid<Foo> = "5".parse().unwrap();
+    assert_eq!(5, f.le_10);
+
+    let err = "x".parse::<Valid<Foo>>().unwrap_err();
+    assert_eq!("invalid digit found in string", err.to_string());
+
+    let err = "20".parse::<Valid<Foo>>().unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
 }
 
 #[test]
@@ -170,7 +366,7 @@ fn test_valid_deref() {
     // Disable validation
     let res = std::panic::catch_unwind(|| {
         let mut f = Valid::new(Foo { le_10: 20 });
-        f.enabled = false;
+        f.state.set_enabled(false);
         let _x = f.le_10;
     });
     assert!(res.is_ok());
@@ -232,25 +428,28 @@ fn test_valid_as_ref() {
     let mut f = Foo::new_valid(10);
 
     let r = f.as_ref();
-    assert!(r.enabled);
+    assert_eq!(None, r.state.enabled());
     assert!(r.is_enabled());
 
     f.enable_validation(false);
     let r = f.as_ref();
-    assert!(!r.enabled);
+    assert_eq!(Some(false), r.state.enabled());
     assert!(!r.is_enabled());
 }
 
 #[test]
 fn test_set_validation() {
     let mut f = Foo::new_valid(10);
-    assert!(f.enabled);
+    assert_eq!(None, f.state.enabled());
+    assert!(f.is_enabled());
 
     f.enable_validation(false);
-    assert!(!f.enabled);
+    assert_eq!(Some(false), f.state.enabled());
+    assert!(!f.is_enabled());
 
     f.enable_validation(true);
-    assert!(f.enabled);
+    assert_eq!(Some(true), f.state.enabled());
+    assert!(f.is_enabled());
 }
 
 #[test]
@@ -260,3 +459,287 @@ fn test_valid_into_inner() {
     let got = f.into_inner();
     assert_eq!(Foo { le_10: 20 }, got);
 }
+
+#[test]
+fn test_valid_try_get() {
+    let f = Foo::new_valid(5);
+    assert_eq!(5, f.try_get().unwrap().le_10);
+
+    let f = Foo::new_valid(20);
+    let err = f.try_get().unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
+
+    // Disable validation
+    let mut f = Foo::new_valid(20);
+    f.enable_validation(false);
+    assert_eq!(20, f.try_get().unwrap().le_10);
+}
+
+#[test]
+fn test_valid_try_get_mut() {
+    let mut f = Foo::new_valid(5);
+    f.try_get_mut().unwrap().le_10 = 20;
+    assert_eq!(20, f.into_inner().le_10);
+
+    let mut f = Foo::new_valid(20);
+    let err = f.try_get_mut().unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
+
+    // Disable validation
+    let mut f = Foo::new_valid(20);
+    f.enable_validation(false);
+    f.try_get_mut().unwrap().le_10 = 30;
+    assert_eq!(30, f.into_inner().le_10);
+}
+
+#[cfg(validit_stripped)]
+#[test]
+fn test_valid_stripped_layout() {
+    use std::mem::align_of;
+    use std::mem::size_of;
+
+    use crate::policy::Callback;
+
+    assert_eq!(size_of::<u8>(), size_of::<Valid<u8>>());
+    assert_eq!(size_of::<Foo>(), size_of::<Valid<Foo>>());
+    assert_eq!(align_of::<Foo>(), align_of::<Valid<Foo>>());
+    assert_eq!(size_of::<Foo>(), size_of::<Valid<Foo, Callback>>());
+    assert_eq!(size_of::<[Foo; 4]>(), size_of::<[Valid<Foo>; 4]>());
+}
+
+#[test]
+fn test_valid_try_new() {
+    let f = Valid::try_new(Foo { le_10: 5 }).unwrap();
+    assert_eq!(5, f.le_10);
+
+    let err = Valid::try_new(Foo { le_10: 20 }).unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
+
+    // It validates even if validation is turned off for the type.
+    struct Off;
+
+    impl Validate for Off {
+        fn validate(&self) -> Result<(), Box<dyn Error>> {
+            Err("invalid".into())
+        }
+    }
+
+    crate::set_type_enabled(std::any::type_name::<Off>(), false);
+    assert!(Valid::try_new(Off).is_err());
+}
+
+#[test]
+fn test_valid_unsized() {
+    // Trait object
+    let list: Vec<Box<Valid<dyn Validate>>> = vec![
+        Box::new(Valid::new(Foo { le_10: 1 })),
+        Box::new(Valid::new(Items { items: vec![1, 2] })),
+    ];
+    for v in &list {
+        assert!(v.try_get().is_ok());
+    }
+
+    let invalid: Box<Valid<dyn Validate>> = Box::new(Valid::new(Foo { le_10: 20 }));
+    assert!(invalid.try_get().is_err());
+}
+
+#[test]
+fn test_valid_container_impls() {
+    // Slice
+    let v: Box<Valid<[Foo]>> = Box::new(Valid::new([Foo { le_10: 1 }, Foo { le_10: 2 }]));
+    assert_eq!(2, v.len());
+    assert_eq!(2, v[1].le_10);
+
+    let mut v: Box<Valid<[Foo]>> = Box::new(Valid::new([Foo { le_10: 1 }, Foo { le_10: 2 }]));
+    v[1].le_10 = 20;
+    let err = v.try_get().unwrap_err();
+    assert!(err.to_string().starts_with("[1]: expect: self.le_10(20) <= 10(10) at "));
+
+    let a = Valid::new(vec![Foo { le_10: 1 }]);
+    let _r: &Valid<Vec<Foo>> = &a;
+    assert_eq!(1, a[0].le_10);
+
+    // Smart pointer to a trait object
+    let invalid: Valid<Arc<dyn Validate>> = Valid::new(Arc::new(Foo { le_10: 20 }));
+    assert!(invalid.try_get().is_err());
+}
+
+#[cfg(feature = "box-impl")]
+#[test]
+fn test_valid_box_impl() {
+    let s: Valid<Box<str>> = Valid::new("foo".into());
+    assert_eq!("foo", &**s);
+
+    let invalid: Valid<Box<Foo>> = Valid::new(Box::new(Foo { le_10: 20 }));
+    assert!(invalid.try_get().is_err());
+}
+
+#[test]
+fn test_valid_modify() {
+    let mut f = Foo::new_valid(1);
+    let ret = f.modify(|f| {
+        f.le_10 = 5;
+        f.le_10 * 2
+    });
+    assert_eq!(10, ret.unwrap());
+    assert_eq!(5, f.le_10);
+
+    // Rollback
+    let err = f
+        .modify(|f| {
+            f.le_10 = 20;
+        })
+        .unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.le_10(20) <= 10(10) at "));
+    assert_eq!(5, f.le_10);
+
+    // Rollback a collection that grows too long
+    let mut items = Items::new_valid(2);
+    let _x = items.items.len();
+    let res = items.modify(|items| items.extend([7, 8]));
+    assert!(res.is_err());
+    assert_eq!(vec![0, 1], items.items);
+
+    // It validates even if validation is disabled.
+    let mut f = Foo::new_valid(1);
+    f.enable_validation(false);
+    assert!(f.modify(|f| f.le_10 = 20).is_err());
+    assert_eq!(1, f.le_10);
+
+    // Repair an invalid value, which is not validated before calling `f`.
+    let mut f = Foo::new_valid(20);
+    f.modify(|f| f.le_10 = 3).unwrap();
+    assert_eq!(3, f.le_10);
+
+    // Rollback if `f` panics.
+    let mut f = Foo::new_valid(1);
+    let res = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let _ = f.modify(|f| {
+            f.le_10 = 7;
+            panic!("oops");
+        });
+    }));
+    assert!(res.is_err());
+    assert_eq!(1, f.le_10);
+}
+
+#[test]
+fn test_valid_access_location() {
+    fn panic_msg<R>(f: impl FnOnce() -> R + UnwindSafe) -> String {
+        let err = std::panic::catch_unwind(f).map(|_| ()).unwrap_err();
+        err.downcast_ref::<String>().unwrap().clone()
+    }
+
+    let f = Foo::new_valid(20);
+    let g = Foo::new_valid(20);
+
+    #[allow(clippy::clone_on_copy)]
+    let cases: Vec<(String, u32)> = vec![
+        (panic_msg(|| f.le_10), line!()),
+        (panic_msg(|| f.clone()), line!()),
+        (panic_msg(|| f == g), line!()),
+        (panic_msg(|| f.partial_cmp(&g)), line!()),
+        (panic_msg(|| f.cmp(&g)), line!()),
+        (panic_msg(|| f.hash(&mut DefaultHasher::new())), line!()),
+        (panic_msg(|| f.as_ref().le_10), line!()),
+    ];
+
+    for (msg, line) in cases {
+        assert!(
+            msg.starts_with("invalid state: expect: self.le_10(20) <= 10(10) at "),
+            "got: {}",
+            msg
+        );
+        assert!(
+            msg.contains(&format!(
+                "\ndetected in `validit::testing::Foo` at {}:{}:",
+                file!(),
+                line
+            )),
+            "got: {}",
+            msg
+        );
+    }
+
+    let mut f = Foo::new_valid(20);
+    let msg = panic_msg(move || f.le_10 = 1);
+    assert!(
+        msg.contains(&format!("at {}:{}:", file!(), line!() - 2)),
+        "got: {}",
+        msg
+    );
+}
+
+#[test]
+fn test_valid_access_error() {
+    let f = Foo::new_valid(20);
+    let err = f.try_get().unwrap_err();
+    let line = line!() - 1;
+
+    assert_eq!("validit::testing::Foo", err.type_name());
+    assert_eq!(file!(), err.location().file());
+    assert_eq!(line, err.location().line());
+    assert_eq!(None, err.context());
+
+    // The error from `validate()` is kept as the source.
+    let source = err.source().unwrap();
+    let source = source.downcast_ref::<ValidationError>().unwrap();
+    assert_eq!(OpKind::LessEqual, source.op);
+    assert_eq!("20", source.left().unwrap().value);
+
+    let mut f = Foo::new_valid(20);
+    let err = f.try_get_mut().unwrap_err().into_source();
+    assert!(err.downcast_ref::<ValidationError>().is_some());
+}
+
+#[cfg(feature = "track-mutation")]
+#[test]
+fn test_valid_track_mutation() {
+    let mut f = Foo::new_valid(1);
+
+    f.le_10 = 20;
+    let mutated = line!() - 1;
+
+    let err = f.try_get().unwrap_err().to_string();
+    let detected = line!() - 1;
+    assert!(
+        err.ends_with(&format!(
+            "\ndetected in `validit::testing::Foo` at {}:{}:17, last mutated at {}:{}:5",
+            file!(),
+            detected,
+            file!(),
+            mutated
+        )),
+        "got: {}",
+        err
+    );
+
+    // Panic message of Deref
+    let res = std::panic::catch_unwind(|| {
+        let _x = f.le_10;
+    });
+    let err = res.unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert!(
+        msg.contains(&format!("last mutated at {}:{}:5", file!(), mutated)),
+        "got: {}",
+        msg
+    );
+
+    // Mutated by an operator
+    let mut f = Foo::new_valid(1);
+    f += 30;
+    let mutated = line!() - 1;
+    let err = f.try_get().unwrap_err().to_string();
+    assert!(
+        err.contains(&format!("last mutated at {}:{}:5", file!(), mutated)),
+        "got: {}",
+        err
+    );
+
+    // Invalid since created
+    let f = Foo::new_valid(20);
+    let err = f.try_get().unwrap_err().to_string();
+    assert!(err.contains("\ndetected in "), "got: {}", err);
+    assert!(!err.contains("last mutated at"), "got: {}", err);
+}
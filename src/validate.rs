@@ -7,3 +7,31 @@ pub trait Validate {
     /// Validate the internal state.
     fn validate(&self) -> Result<(), Box<dyn Error>>;
 }
+
+/// Call [`Validate::validate()`], and record the statistics with feature `stats`.
+///
+/// With feature `tracing`, it runs in a `TRACE` span `validate`. With feature `tracing` or `log`,
+/// a failure emits an event, see [`event`](crate::event).
+pub(crate) fn run<T>(inner: &T) -> Result<(), Box<dyn Error>>
+where T: Validate + ?Sized {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!(
+        target: "validit",
+        "validate",
+        type_name = std::any::type_name::<T>()
+    )
+    .entered();
+
+    #[cfg(feature = "stats")]
+    let res = crate::stats::measure(inner);
+
+    #[cfg(not(feature = "stats"))]
+    let res = inner.validate();
+
+    #[cfg(any(feature = "tracing", feature = "log"))]
+    if let Err(e) = &res {
+        crate::event::failed::<T>(e.as_ref());
+    }
+
+    res
+}
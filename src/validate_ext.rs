@@ -1,5 +1,7 @@
 //! Extension trait to ease the use of `Validate` trait.
 
+use std::error::Error;
+
 use crate::Valid;
 use crate::Validate;
 
@@ -7,6 +9,11 @@ pub trait ValidateExt: Validate {
     /// Return an always valid instance `Valid<Self>` that enables internal state validation.
     fn valid(self) -> Valid<Self>
     where Self: Sized;
+
+    /// Return `Valid<Self>` if the state is valid, otherwise return the error from
+    /// [`Validate::validate()`]. See [`Valid::try_new()`].
+    fn try_valid(self) -> Result<Valid<Self>, Box<dyn Error>>
+    where Self: Sized;
 }
 
 impl<T: Validate> ValidateExt for T {
@@ -14,4 +21,9 @@ impl<T: Validate> ValidateExt for T {
     where Self: Sized {
         Valid::new(self)
     }
+
+    fn try_valid(self) -> Result<Valid<Self>, Box<dyn Error>>
+    where Self: Sized {
+        Valid::try_new(self)
+    }
 }
@@ -1,13 +1,55 @@
 use std::error::Error;
+use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::Validate;
 
-impl<T: Validate> Validate for &T {
+/// Forward to the pointee, e.g., `Box<T>` or `Arc<dyn Validate>`.
+macro_rules! impl_validate_pointer {
+    ($ptr: ident) => {
+        impl<T: Validate + ?Sized> Validate for $ptr<T> {
+            fn validate(&self) -> Result<(), Box<dyn Error>> {
+                T::validate(self)
+            }
+        }
+    };
+}
+
+impl<T: Validate + ?Sized> Validate for &T {
     fn validate(&self) -> Result<(), Box<dyn Error>> {
         (*self).validate()
     }
 }
 
+// `Box` is `#[fundamental]`, a dependent crate can implement `Validate` for `Box<Local>` and would
+// conflict with a blanket impl, thus it is opt-in.
+#[cfg(feature = "box-impl")]
+impl_validate_pointer!(Box);
+impl_validate_pointer!(Rc);
+impl_validate_pointer!(Arc);
+
+/// A slice is valid if every element is valid.
+impl<T: Validate> Validate for [T] {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        for (i, elt) in self.iter().enumerate() {
+            elt.validate().map_err(|e| format!("[{}]: {}", i, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Validate, const N: usize> Validate for [T; N] {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        self.as_slice().validate()
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        self.as_slice().validate()
+    }
+}
+
 /// Dummy impl Validate for primitive types
 macro_rules! impl_validate {
     ($typ: ty) => {
@@ -31,5 +73,5 @@ impl_validate!(i8);
 impl_validate!(i16);
 impl_validate!(i32);
 impl_validate!(i64);
-impl_validate!(&str);
+impl_validate!(str);
 impl_validate!(String);
@@ -0,0 +1,198 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// The error returned by the validation macros such as [`less!`](crate::less) and
+/// [`be_true!`](crate::be_true), which keeps what is checked.
+///
+/// The `Display` output is the same as the message of the macros, e.g.,
+/// `expect: a(3) <= b(2) at src/foo.rs:10`, so that a caller can get the operands back without
+/// parsing it.
+///
+/// The operands of all kinds of checks are kept in one list, in the order they appear in the
+/// macro: a comparison has exactly two, the left and the right one, which are also returned by
+/// [`left()`](Self::left) and [`right()`](Self::right); a [`be_true!`](crate::be_true) call has
+/// one for each argument, zero up to eight. A single list keeps the error one shape for every
+/// [`OpKind`], so that a caller can render or iterate the operands without matching on the kind:
+///
+/// ```
+/// # use std::error::Error;
+/// # use validit::OpKind;
+/// # use validit::ValidationError;
+/// fn check(a: u64, b: u64) -> Result<(), Box<dyn Error>> {
+///     validit::less_equal!(a, b);
+///     Ok(())
+/// }
+///
+/// let err = check(3, 2).unwrap_err();
+/// let err = err.downcast_ref::<ValidationError>().unwrap();
+///
+/// assert_eq!(OpKind::LessEqual, err.op);
+/// assert_eq!("a", err.left().unwrap().expr);
+/// assert_eq!("3", err.left().unwrap().value);
+/// assert!(err.to_string().starts_with("expect: a(3) <= b(2) at "));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// What is expected of the operands.
+    pub op: OpKind,
+
+    /// The left and the right operand of a comparison, or the arguments of a function call, in
+    /// the order they appear in the check.
+    pub operands: Vec<Operand>,
+
+    /// The file where the check is defined.
+    pub file: &'static str,
+
+    /// The line where the check is defined.
+    pub line: u32,
+
+    /// The column where the check is defined.
+    pub column: u32,
+
+    /// The name of the violated invariant, such as the `name` of a derived `check`, or the enum
+    /// variant it belongs to. It is prepended to the message.
+    pub invariant: Option<String>,
+}
+
+/// The kind of check of a [`ValidationError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpKind {
+    /// `a < b`, by [`less!`](crate::less).
+    Less,
+
+    /// `a <= b`, by [`less_equal!`](crate::less_equal).
+    LessEqual,
+
+    /// `a > b`, by [`greater!`](crate::greater).
+    Greater,
+
+    /// `a >= b`, by [`greater_equal!`](crate::greater_equal).
+    GreaterEqual,
+
+    /// `a == b`, by [`equal!`](crate::equal).
+    Equal,
+
+    /// A function call with the operands as arguments returns `true`, by
+    /// [`be_true!`](crate::be_true). It holds the function, such as `self.is_valid`.
+    BeTrue(&'static str),
+}
+
+impl OpKind {
+    /// Return the operator of a comparison, such as `<=`, or `None` for a function call.
+    pub fn as_operator(&self) -> Option<&'static str> {
+        match self {
+            OpKind::Less => Some("<"),
+            OpKind::LessEqual => Some("<="),
+            OpKind::Greater => Some(">"),
+            OpKind::GreaterEqual => Some(">="),
+            OpKind::Equal => Some("=="),
+            OpKind::BeTrue(_) => None,
+        }
+    }
+}
+
+/// An operand of a [`ValidationError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operand {
+    /// The source text of the expression, such as `self.a`.
+    pub expr: &'static str,
+
+    /// The `Debug` rendering of the value of the expression.
+    pub value: String,
+}
+
+impl Operand {
+    /// Create an operand from the source text of an expression and its value.
+    pub fn new(expr: &'static str, value: &dyn Debug) -> Self {
+        Self {
+            expr,
+            value: format!("{:?}", value),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.expr, self.value)
+    }
+}
+
+impl ValidationError {
+    /// Create an error of a failed check defined at `file:line:column`.
+    pub fn new(
+        op: OpKind,
+        operands: Vec<Operand>,
+        file: &'static str,
+        line: u32,
+        column: u32,
+    ) -> Self {
+        Self {
+            op,
+            operands,
+            file,
+            line,
+            column,
+            invariant: None,
+        }
+    }
+
+    /// Set the name of the violated invariant.
+    ///
+    /// If there is already one, the new name is prepended to it, as the message of a nested
+    /// invariant is.
+    pub fn with_invariant(mut self, name: impl ToString) -> Self {
+        let name = name.to_string();
+        self.invariant = Some(match self.invariant.take() {
+            Some(inner) => format!("{}: {}", name, inner),
+            None => name,
+        });
+        self
+    }
+
+    /// The left operand of a comparison.
+    pub fn left(&self) -> Option<&Operand> {
+        self.op.as_operator().and(self.operands.first())
+    }
+
+    /// The right operand of a comparison.
+    pub fn right(&self) -> Option<&Operand> {
+        self.op.as_operator().and(self.operands.get(1))
+    }
+
+    /// Render the message without the invariant name and the location, such as
+    /// `expect: a(3) <= b(2)`.
+    pub fn expectation(&self) -> String {
+        match self.op {
+            OpKind::BeTrue(func) => {
+                let args = self.operands.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+                format!("expect to be true: {}({})", func, args.join(", "))
+            }
+            op => {
+                let operator = op.as_operator().unwrap_or_default();
+                let operands = self.operands.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+                format!("expect: {}", operands.join(&format!(" {} ", operator)))
+            }
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(invariant) = &self.invariant {
+            write!(f, "{}: ", invariant)?;
+        }
+        write!(f, "{} at {}:{}", self.expectation(), self.file, self.line)
+    }
+}
+
+impl Error for ValidationError {}
+
+#[cfg(feature = "macros")]
+impl From<ValidationError> for anyerror::AnyError {
+    fn from(e: ValidationError) -> Self {
+        anyerror::AnyError::error(e)
+    }
+}
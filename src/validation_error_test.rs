@@ -0,0 +1,164 @@
+use std::error::Error;
+
+use crate::OpKind;
+use crate::Operand;
+use crate::ValidationError;
+use crate::be_true;
+use crate::equal;
+use crate::greater;
+use crate::greater_equal;
+use crate::less;
+use crate::less_equal;
+
+fn downcast(res: Result<(), Box<dyn Error>>) -> ValidationError {
+    *res.unwrap_err().downcast::<ValidationError>().unwrap()
+}
+
+#[test]
+fn test_validation_error_send_sync() {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<ValidationError>();
+}
+
+#[test]
+fn test_validation_error_compare() {
+    let check = |a: u64, b: u64| -> Result<(), Box<dyn Error>> {
+        less_equal!(a, b + 1);
+        Ok(())
+    };
+    let line = line!() - 3;
+
+    let err = downcast(check(5, 2));
+
+    assert_eq!(OpKind::LessEqual, err.op);
+    assert_eq!(Some(&Operand::new("a", &5u64)), err.left());
+    assert_eq!(Some(&Operand::new("b + 1", &3u64)), err.right());
+    assert_eq!(file!(), err.file);
+    assert_eq!(line, err.line);
+    assert_eq!(9, err.column);
+    assert_eq!(None, err.invariant);
+
+    assert_eq!(
+        format!("expect: a(5) <= b + 1(3) at {}:{}", file!(), line),
+        err.to_string()
+    );
+}
+
+#[test]
+fn test_validation_error_op_kind() {
+    let op = |res: Result<(), Box<dyn Error>>| downcast(res).op;
+
+    assert_eq!(
+        OpKind::Less,
+        op((|| {
+            less!(2, 1);
+            Ok(())
+        })())
+    );
+    assert_eq!(
+        OpKind::Greater,
+        op((|| {
+            greater!(1, 2);
+            Ok(())
+        })())
+    );
+    assert_eq!(
+        OpKind::GreaterEqual,
+        op((|| {
+            greater_equal!(1, 2);
+            Ok(())
+        })())
+    );
+    assert_eq!(
+        OpKind::Equal,
+        op((|| {
+            equal!(1, 2);
+            Ok(())
+        })())
+    );
+}
+
+#[test]
+fn test_validation_error_be_true() {
+    fn between(l: u64, x: u64, r: u64) -> bool {
+        l <= x && x <= r
+    }
+    fn never() -> bool {
+        false
+    }
+
+    let err = downcast((|| {
+        be_true!(between(1, 5, 3));
+        Ok(())
+    })());
+
+    assert_eq!(OpKind::BeTrue("between"), err.op);
+    assert_eq!(
+        vec![
+            Operand::new("1", &1u64),
+            Operand::new("5", &5u64),
+            Operand::new("3", &3u64),
+        ],
+        err.operands
+    );
+    assert_eq!(None, err.left());
+    assert!(err.to_string().starts_with("expect to be true: between(1(1), 5(5), 3(3)) at "));
+
+    let err = downcast((|| {
+        be_true!(never());
+        Ok(())
+    })());
+    assert!(err.to_string().starts_with("expect to be true: never() at "));
+}
+
+#[test]
+fn test_validation_error_invariant() {
+    let err = ValidationError::new(
+        OpKind::Equal,
+        vec![Operand::new("a", &1), Operand::new("b", &2)],
+        "foo.rs",
+        3,
+        4,
+    );
+
+    let err = err.with_invariant("inner");
+    assert_eq!(Some("inner".to_string()), err.invariant);
+    assert_eq!("inner: expect: a(1) == b(2) at foo.rs:3", err.to_string());
+
+    let err = err.with_invariant("outer");
+    assert_eq!(Some("outer: inner".to_string()), err.invariant);
+    assert_eq!(
+        "outer: inner: expect: a(1) == b(2) at foo.rs:3",
+        err.to_string()
+    );
+    assert_eq!("expect: a(1) == b(2)", err.expectation());
+}
+
+#[test]
+fn test_validation_error_with_invariant() {
+    let err = ValidationError::new(
+        OpKind::Less,
+        vec![Operand::new("a", &1), Operand::new("b", &0)],
+        "foo.rs",
+        3,
+        4,
+    );
+
+    let err = crate::macros::with_invariant("name", Box::new(err));
+    let err = err.downcast_ref::<ValidationError>().unwrap();
+    assert_eq!(Some("name".to_string()), err.invariant);
+
+    let err = crate::macros::with_invariant("name", "foo".into());
+    assert_eq!("name: foo", err.to_string());
+}
+
+#[test]
+fn test_validation_error_into_anyerror() -> Result<(), anyerror::AnyError> {
+    let res = (|| {
+        less!(1, 0);
+        Ok::<(), anyerror::AnyError>(())
+    })();
+    assert!(res.unwrap_err().to_string().starts_with("expect: 1(1) < 0(0) at "));
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::panic::Location;
+
+use crate::AccessKind;
+use crate::FailurePolicy;
+use crate::Valid;
+use crate::Validate;
+use crate::policy::Panic;
+
+/// A guard for writing to a [`Valid<T>`], which validates the state when it is dropped.
+///
+/// It is returned by [`Valid::write()`]. Unlike writing through `DerefMut` of `Valid<T>`, an
+/// invalid state is detected when the guard is dropped, thus the panic points at the code that
+/// wrote the invalid state, instead of the next access to it.
+///
+/// ```
+/// # use std::error::Error;
+/// # use std::panic::catch_unwind;
+/// # use validit::Valid;
+/// # use validit::Validate;
+/// struct Foo { a: u64 }
+/// impl Validate for Foo {
+///     fn validate(&self) -> Result<(), Box<dyn Error>> {
+///         validit::less_equal!(self.a, 10);
+///         Ok(())
+///     }
+/// }
+///
+/// let res = catch_unwind(|| {
+///     let mut f = Valid::new(Foo { a: 5 });
+///     f.write().a = 20; // panic: ... detected in `Foo` after write at src/main.rs:...
+/// });
+/// assert!(res.is_err());
+/// ```
+pub struct WriteGuard<'a, T, P = Panic>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    valid: &'a mut Valid<T, P>,
+
+    /// Where the guard is created, to locate the write that breaks the state.
+    location: &'static Location<'static>,
+}
+
+impl<'a, T, P> WriteGuard<'a, T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    pub(crate) fn new(valid: &'a mut Valid<T, P>, location: &'static Location<'static>) -> Self {
+        Self { valid, location }
+    }
+}
+
+impl<T, P> Deref for WriteGuard<'_, T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.valid.inner
+    }
+}
+
+impl<T, P> DerefMut for WriteGuard<'_, T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.valid.inner
+    }
+}
+
+impl<T, P> Drop for WriteGuard<'_, T, P>
+where
+    T: Validate + ?Sized,
+    P: FailurePolicy<T>,
+{
+    fn drop(&mut self) {
+        // Do not panic again if it is already panicking, which aborts the process.
+        if !std::thread::panicking()
+            && let Err(e) = self.valid.check()
+        {
+            let e = self.valid.access_error(e, AccessKind::AfterWrite, self.location);
+            self.valid.state.on_failure(&e);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::any::type_name;
+
+use crate::Valid;
+use crate::testing::Foo;
+
+#[test]
+fn test_write_guard_valid() {
+    let mut f = Valid::new(Foo { le_10: 1 });
+    {
+        let mut w = f.write();
+        w.le_10 += 5;
+        assert_eq!(6, w.le_10);
+    }
+    assert_eq!(6, f.le_10);
+}
+
+#[test]
+fn test_write_guard_panic_on_drop() {
+    let res = std::panic::catch_unwind(|| {
+        let mut f = Valid::new(Foo { le_10: 1 });
+        f.write().le_10 = 20;
+    });
+    let line = line!() - 2;
+
+    let err = res.unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert!(
+        msg.starts_with("invalid state: expect: self.le_10(20) <= 10(10) at "),
+        "got: {}",
+        msg
+    );
+    assert!(
+        msg.contains(&format!(
+            "\ndetected in `{}` after write at {}:{}:",
+            type_name::<Foo>(),
+            file!(),
+            line
+        )),
+        "got: {}",
+        msg
+    );
+}
+
+#[test]
+fn test_write_guard_panic_on_invalid_before_write() {
+    let res = std::panic::catch_unwind(|| {
+        let mut f = Valid::new(Foo { le_10: 20 });
+        let _w = f.write();
+    });
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_write_guard_disabled() {
+    let res = std::panic::catch_unwind(|| {
+        let mut f = Valid::new(Foo { le_10: 1 });
+        f.enable_validation(false);
+        f.write().le_10 = 20;
+    });
+    assert!(res.is_ok());
+}
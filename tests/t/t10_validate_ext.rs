@@ -4,6 +4,7 @@ use std::ops::Deref;
 use validit::Validate;
 use validit::ValidateExt;
 
+#[derive(Debug)]
 struct LessThan5(u64);
 
 impl Validate for LessThan5 {
@@ -24,3 +25,12 @@ fn test_validate_ext() {
 
     assert!(res.is_err());
 }
+
+#[test]
+fn test_validate_ext_try_valid() {
+    let a = LessThan5(1).try_valid().unwrap();
+    assert_eq!(1, a.0);
+
+    let err = LessThan5(10).try_valid().unwrap_err();
+    assert!(err.to_string().starts_with("expect: self.0(10) < 5(5) at "));
+}